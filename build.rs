@@ -0,0 +1,327 @@
+//! Reads the metadata headers of the scripts in `src/commands`, and writes them to
+//! `scripts_metadata.rs` in `OUT_DIR`, for the TUI to include
+//!
+//! A header is made of `# @key value` comments at the top of a script, before its first line of
+//! code:
+//!
+//! ```sh
+//! #!/bin/sh -e
+//! # @description Installs Docker
+//! # @tags docker,container
+//! # @privilege sudo
+//! # @requires curl,systemctl
+//! # @param port:int "The port Docker listens on"
+//! # @retry 3
+//! # @timeout 600
+//! # @rollback docker-uninstall.sh
+//! ```
+//!
+//! `@privilege` is who the script runs as: `user`, the default, `sudo` to run it with sudo, or
+//! `root` for scripts that only run when linutil itself does. Every `@param` is a value that's
+//! asked for before the script runs, as a `string`, `int`, `path` or `bool`. `@retry` is how
+//! many more times the script is run if it fails. `@timeout` is how many seconds it may run for,
+//! instead of the timeout in the config. `@rollback` is a script that undoes this one,
+//! relative to its directory. It's run when this script, or one after it in a chain, fails
+//!
+//! The same metadata is written to `linutil-scripts.json` in `OUT_DIR`, for tools that want to
+//! know the scripts without running linutil. Set `LINUTIL_INVENTORY_DIR` to get a copy of it in
+//! that directory too, relative paths are relative to the crate
+//!
+//! The variables linutil sets for the scripts it runs are written there too, as `SCRIPT_ENV`,
+//! with what each of them holds
+//!
+//! Set `LINUTIL_NOSCRIPTS` to leave out the metadata of every script, for testing linutil without
+//! any
+
+use std::{
+    env,
+    fmt::Write,
+    fs,
+    path::{Path, PathBuf},
+};
+
+const COMMANDS_DIR: &str = "src/commands";
+
+/// The helpers every script starts with, it isn't a script by itself
+const COMMON_SCRIPT: &str = "common-script.sh";
+
+const INVENTORY_FILE: &str = "linutil-scripts.json";
+
+#[derive(Debug, Default, PartialEq, Eq)]
+struct ScriptMeta {
+    /// Relative to `src/commands`
+    path: String,
+    description: String,
+    author: String,
+    tags: Vec<String>,
+    /// Commands that have to be on `PATH` for the script to run
+    requires: Vec<String>,
+    /// `user`, `sudo` or `root`
+    privilege: String,
+    /// The name, kind and label of each `@param`
+    params: Vec<(String, String, String)>,
+    retry: u32,
+    /// In seconds, at least 1
+    timeout: Option<u64>,
+    /// Relative to `src/commands`
+    rollback: Option<String>,
+}
+
+/// The kinds of `@param`, with the `ParamKind` each of them is
+const PARAM_KINDS: [(&str, &str); 4] = [
+    ("string", "String"),
+    ("int", "Int"),
+    ("path", "Path"),
+    ("bool", "Bool"),
+];
+
+/// The variables the scripts get, with what they hold. Those linutil couldn't detect aren't set
+const SCRIPT_ENV: [(&str, &str); 5] = [
+    (
+        "LINUTIL_DISTRO",
+        "The ID of the distribution, from os-release",
+    ),
+    ("LINUTIL_PM", "The binary of the package manager"),
+    ("LINUTIL_ARCH", "The architecture of the CPU, like x86_64"),
+    (
+        "LINUTIL_PM_INSTALL_ARGS",
+        "What goes between the package manager and the packages, to install them",
+    ),
+    (
+        "LINUTIL_HAS_<TOOL>",
+        "1 if the language package manager is on PATH, 0 if it isn't. For CARGO, PIP, NPM, YARN, \
+         PNPM, GEM and COMPOSER",
+    ),
+];
+
+/// The `@privilege` values, with the `PrivilegeMode` each of them is
+const PRIVILEGES: [(&str, &str); 3] = [
+    ("user", "AsUser"),
+    ("sudo", "AutoSudo"),
+    ("root", "RequireSudo"),
+];
+
+fn main() {
+    println!("cargo:rerun-if-changed={COMMANDS_DIR}");
+    println!("cargo:rerun-if-env-changed=LINUTIL_INVENTORY_DIR");
+    println!("cargo:rerun-if-env-changed=LINUTIL_NOSCRIPTS");
+
+    let mut scripts = Vec::new();
+    if env::var_os("LINUTIL_NOSCRIPTS").is_none_or(|value| value.is_empty()) {
+        find_scripts(Path::new(COMMANDS_DIR), &mut scripts);
+    }
+    scripts.sort();
+
+    let metadata: Vec<ScriptMeta> = scripts
+        .iter()
+        .map(|script| {
+            let contents = fs::read_to_string(script)
+                .unwrap_or_else(|err| panic!("could not read {}: {err}", script.display()));
+            let path = script.strip_prefix(COMMANDS_DIR).unwrap();
+            parse_header(&path.to_string_lossy(), &contents)
+        })
+        .collect();
+
+    let out_dir = PathBuf::from(env::var_os("OUT_DIR").unwrap());
+    fs::write(
+        out_dir.join("scripts_metadata.rs"),
+        metadata_source(&metadata),
+    )
+    .unwrap();
+
+    let inventory = inventory_json(&metadata);
+    fs::write(out_dir.join(INVENTORY_FILE), &inventory).unwrap();
+    if let Some(dir) = env::var_os("LINUTIL_INVENTORY_DIR").filter(|dir| !dir.is_empty()) {
+        let dir = PathBuf::from(env::var_os("CARGO_MANIFEST_DIR").unwrap()).join(dir);
+        fs::create_dir_all(&dir)
+            .and_then(|()| fs::write(dir.join(INVENTORY_FILE), &inventory))
+            .unwrap_or_else(|err| panic!("could not write to {}: {err}", dir.display()));
+    }
+}
+
+/// Every script under `dir`, except for the common one, with the directories they're in
+fn find_scripts(dir: &Path, scripts: &mut Vec<PathBuf>) {
+    let entries =
+        fs::read_dir(dir).unwrap_or_else(|err| panic!("could not read {}: {err}", dir.display()));
+    for entry in entries {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            // Changes to the files in it don't always change the directory itself
+            println!("cargo:rerun-if-changed={}", path.display());
+            find_scripts(&path, scripts);
+        } else if path.extension().is_some_and(|ext| ext == "sh")
+            && path.file_name().is_some_and(|name| name != COMMON_SCRIPT)
+        {
+            println!("cargo:rerun-if-changed={}", path.display());
+            scripts.push(path);
+        }
+    }
+}
+
+/// Reads the `# @key value` lines at the top of the script. Keys it doesn't know are left out
+fn parse_header(path: &str, contents: &str) -> ScriptMeta {
+    let mut meta = ScriptMeta {
+        path: path.to_string(),
+        privilege: "user".to_string(),
+        ..ScriptMeta::default()
+    };
+    // The header ends with the first line that isn't a comment
+    let header = contents
+        .lines()
+        .map(str::trim)
+        .take_while(|line| line.is_empty() || line.starts_with('#'));
+    for line in header {
+        let Some(field) = line.strip_prefix('#').map(str::trim_start) else {
+            continue;
+        };
+        let Some((key, value)) = field.strip_prefix('@').and_then(|field| {
+            let (key, value) = field.split_once(char::is_whitespace)?;
+            Some((key, value.trim()))
+        }) else {
+            continue;
+        };
+        match key {
+            "description" => meta.description = value.to_string(),
+            "author" => meta.author = value.to_string(),
+            "privilege" => {
+                if !PRIVILEGES.iter().any(|&(name, _)| name == value) {
+                    panic!("{path}: the privilege has to be user, sudo or root, not {value}");
+                }
+                meta.privilege = value.to_string();
+            }
+            "param" => meta.params.push(parse_param(path, value)),
+            "rollback" => meta.rollback = Some(rollback_path(path, value)),
+            "retry" => {
+                meta.retry = value.parse().unwrap_or_else(|_| {
+                    panic!("{path}: retry is how many more times to run the script, not {value}")
+                })
+            }
+            "timeout" => {
+                let secs = value.parse().ok().filter(|&secs| secs > 0);
+                meta.timeout = Some(secs.unwrap_or_else(|| {
+                    panic!("{path}: the timeout is a number of seconds, at least 1, not {value}")
+                }))
+            }
+            "tags" => meta.tags = comma_separated(value),
+            "requires" => meta.requires = comma_separated(value),
+            _ => {}
+        }
+    }
+    meta
+}
+
+/// The path of the rollback script relative to `src/commands`, it has to exist
+fn rollback_path(path: &str, value: &str) -> String {
+    let rollback = Path::new(path)
+        .parent()
+        .unwrap_or(Path::new(""))
+        .join(value);
+    if !Path::new(COMMANDS_DIR).join(&rollback).is_file() {
+        panic!("{path}: there's no rollback script {}", rollback.display());
+    }
+    rollback.to_string_lossy().into_owned()
+}
+
+/// `name:kind "Label"`, the quotes are optional
+fn parse_param(path: &str, value: &str) -> (String, String, String) {
+    let (spec, label) = value.split_once(char::is_whitespace).unwrap_or((value, ""));
+    let Some((name, kind)) = spec.split_once(':') else {
+        panic!("{path}: a param is `name:kind \"Label\"`, not {value}");
+    };
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        panic!("{path}: param names are letters, digits and underscores, not {name}");
+    }
+    if !PARAM_KINDS.iter().any(|&(known, _)| known == kind) {
+        panic!("{path}: the kind of a param is string, int, path or bool, not {kind}");
+    }
+    let label = label.trim();
+    let label = label
+        .strip_prefix('"')
+        .and_then(|label| label.strip_suffix('"'))
+        .unwrap_or(label);
+    (name.to_string(), kind.to_string(), label.to_string())
+}
+
+fn comma_separated(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// The generated Rust, `ScriptMeta` itself is defined in the TUI
+fn metadata_source(metadata: &[ScriptMeta]) -> String {
+    let mut source = String::from("pub const SCRIPT_METADATA: &[ScriptMeta] = &[\n");
+    for meta in metadata {
+        let (_, privilege) = PRIVILEGES
+            .iter()
+            .find(|&&(name, _)| name == meta.privilege)
+            .unwrap();
+        let rollback = match &meta.rollback {
+            Some(rollback) => {
+                let contents = Path::new(&env::var_os("CARGO_MANIFEST_DIR").unwrap())
+                    .join(COMMANDS_DIR)
+                    .join(rollback);
+                format!(
+                    "Some(Rollback {{ path: {rollback:?}, command: include_str!({:?}) }})",
+                    contents.to_string_lossy()
+                )
+            }
+            None => "None".to_string(),
+        };
+        let params: Vec<String> = meta
+            .params
+            .iter()
+            .map(|(name, kind, label)| {
+                let (_, kind) = PARAM_KINDS
+                    .iter()
+                    .find(|&&(known, _)| known == kind)
+                    .unwrap();
+                format!(
+                    "ScriptParam {{ name: {name:?}, kind: ParamKind::{kind}, label: {label:?} }}"
+                )
+            })
+            .collect();
+        // Debug formatting quotes and escapes the same way Rust string literals do
+        writeln!(
+            source,
+            "    ScriptMeta {{ path: {:?}, description: {:?}, author: {:?}, tags: &{:?}, \
+             requires: &{:?}, privilege: PrivilegeMode::{privilege}, params: &[{}], retry: {}, \
+             timeout: {:?}, rollback: {rollback} }},",
+            meta.path,
+            meta.description,
+            meta.author,
+            meta.tags,
+            meta.requires,
+            params.join(", "),
+            meta.retry,
+            meta.timeout
+        )
+        .unwrap();
+    }
+    source.push_str("];\n\npub const SCRIPT_ENV: &[(&str, &str)] = &[\n");
+    for (name, description) in SCRIPT_ENV {
+        writeln!(source, "    ({name:?}, {description:?}),").unwrap();
+    }
+    source.push_str("];\n");
+    source
+}
+
+/// An array with the path, description, tags, requirements and privilege of every script
+fn inventory_json(metadata: &[ScriptMeta]) -> String {
+    let scripts: Vec<serde_json::Value> = metadata
+        .iter()
+        .map(|meta| {
+            serde_json::json!({
+                "path": meta.path,
+                "description": meta.description,
+                "tags": meta.tags,
+                "requires": meta.requires,
+                "privilege": meta.privilege,
+            })
+        })
+        .collect();
+    serde_json::to_string_pretty(&scripts).unwrap() + "\n"
+}
@@ -0,0 +1,228 @@
+use std::fmt;
+
+use crate::{
+    bookmarks::BookmarkStore,
+    config::{KeyBindings, TuiConfig},
+    history::History,
+    list::{lookup_command, CustomList},
+    theme::ThemeChoice,
+};
+use tui::systeminfo::System;
+
+/// Everything the TUI starts with. Put it together with `TuiApp::builder()`
+pub struct TuiApp {
+    /// `None` if the system couldn't be detected, or isn't yet. The status bar does without it
+    pub system: Option<System>,
+    pub config: TuiConfig,
+    pub keys: KeyBindings,
+    /// The index of the theme in `THEMES`, it's set once the TUI runs
+    pub theme: usize,
+    pub list: CustomList,
+}
+
+impl TuiApp {
+    pub fn builder() -> TuiAppBuilder {
+        TuiAppBuilder::default()
+    }
+}
+
+/// Checks the parts of the TUI as they're given, so what's wrong with them can be said before the
+/// TUI takes over the terminal
+#[derive(Default)]
+pub struct TuiAppBuilder {
+    system: Option<System>,
+    config: TuiConfig,
+    keys: KeyBindings,
+    /// The theme, if it was picked. Otherwise it's the one in the config, or the default one
+    theme: Option<usize>,
+    history: History,
+    bookmarks: BookmarkStore,
+}
+
+impl TuiAppBuilder {
+    /// Without it, the TUI detects the system in the background once it's up. Fails if os-release
+    /// doesn't say which distribution it is, since that's what the scripts go by
+    pub fn system(mut self, system: System) -> Result<Self, AppError> {
+        if system.id().is_empty() {
+            return Err(AppError::UnknownDistribution);
+        }
+        self.system = Some(system);
+        Ok(self)
+    }
+
+    /// Fails if the whitelist or the blacklist names a command there isn't, or the timeout would
+    /// kill every script right away
+    pub fn config(mut self, config: TuiConfig) -> Result<Self, AppError> {
+        let mut listed = config.whitelist.iter().chain(&config.blacklist).flatten();
+        if let Some(name) = listed.find(|name| lookup_command(name).is_none()) {
+            return Err(AppError::UnknownCommand(name.clone()));
+        }
+        if config.timeout == Some(0) {
+            return Err(AppError::ZeroTimeout);
+        }
+        self.config = config;
+        Ok(self)
+    }
+
+    pub fn theme(mut self, theme: ThemeChoice) -> Self {
+        self.theme = Some(theme.index());
+        self
+    }
+
+    /// The theme without icons and RGB colors, for terminals that can't show them
+    pub fn compat(mut self) -> Self {
+        self.theme = Some(0);
+        self
+    }
+
+    /// Fails if there's no way to quit, or to answer a prompt
+    pub fn key_bindings(mut self, keys: KeyBindings) -> Result<Self, AppError> {
+        let required = [
+            ("quit", &keys.quit),
+            ("confirm", &keys.confirm),
+            ("abort", &keys.abort),
+        ];
+        if let Some((action, _)) = required.iter().find(|(_, keys)| keys.is_empty()) {
+            return Err(AppError::UnboundKey(action));
+        }
+        self.keys = keys;
+        Ok(self)
+    }
+
+    pub fn history(mut self, history: History) -> Self {
+        self.history = history;
+        self
+    }
+
+    pub fn bookmarks(mut self, bookmarks: BookmarkStore) -> Self {
+        self.bookmarks = bookmarks;
+        self
+    }
+
+    /// Fails if the whitelist and the blacklist leave no command to show
+    pub fn build(self) -> Result<TuiApp, AppError> {
+        let list = CustomList::new(&self.config, self.history, self.bookmarks);
+        if list.is_empty() {
+            return Err(AppError::NoCommands);
+        }
+        let theme = self
+            .theme
+            .or(self.config.theme.map(ThemeChoice::index))
+            .unwrap_or(ThemeChoice::Dark.index());
+        Ok(TuiApp {
+            system: self.system,
+            config: self.config,
+            keys: self.keys,
+            theme,
+            list,
+        })
+    }
+}
+
+/// Why the TUI can't start with what it was given
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AppError {
+    /// The whitelist or the blacklist has a name that isn't a command
+    UnknownCommand(String),
+    /// The timeout is 0 seconds
+    ZeroTimeout,
+    /// The action has no keys, but can't do without
+    UnboundKey(&'static str),
+    /// os-release has no `ID`
+    UnknownDistribution,
+    /// Every command is left out by the whitelist or the blacklist
+    NoCommands,
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownCommand(name) => write!(f, "there's no command called \"{name}\""),
+            Self::ZeroTimeout => write!(f, "the timeout has to be at least 1 second"),
+            Self::UnboundKey(action) => write!(f, "{action} needs at least one key"),
+            Self::UnknownDistribution => {
+                write!(f, "os-release doesn't say which distribution this is")
+            }
+            Self::NoCommands => write!(f, "the whitelist and the blacklist leave no commands"),
+        }
+    }
+}
+
+impl std::error::Error for AppError {}
+
+#[test]
+fn test_builder_checks_config() {
+    let config = |whitelist: &[&str], timeout| TuiConfig {
+        whitelist: Some(whitelist.iter().map(|name| name.to_string()).collect()),
+        timeout,
+        ..TuiConfig::default()
+    };
+    assert!(TuiApp::builder()
+        .config(config(
+            &["Kitty Setup", "dotfiles/kitty-setup.sh"],
+            Some(60)
+        ))
+        .is_ok());
+    assert_eq!(
+        TuiApp::builder()
+            .config(config(&["Kitty Setup", "kity-setup"], None))
+            .err(),
+        Some(AppError::UnknownCommand("kity-setup".to_string()))
+    );
+    assert_eq!(
+        TuiApp::builder().config(config(&[], Some(0))).err(),
+        Some(AppError::ZeroTimeout)
+    );
+}
+
+#[test]
+fn test_builder_checks_keys() {
+    let keys = KeyBindings {
+        abort: Vec::new(),
+        ..KeyBindings::default()
+    };
+    assert_eq!(
+        TuiApp::builder().key_bindings(keys).err(),
+        Some(AppError::UnboundKey("abort"))
+    );
+}
+
+#[test]
+fn test_builder_picks_theme() {
+    let config = TuiConfig {
+        theme: Some(ThemeChoice::Light),
+        ..TuiConfig::default()
+    };
+    let theme = |builder: TuiAppBuilder| builder.build().unwrap().theme;
+    let builder = || TuiApp::builder().config(config.clone()).unwrap();
+    assert_eq!(theme(builder()), ThemeChoice::Light.index());
+    assert_eq!(theme(builder().theme(ThemeChoice::Dark)), 1);
+    assert_eq!(theme(builder().compat()), 0);
+}
+
+#[test]
+fn test_builder_keeps_detected_system() {
+    assert!(TuiApp::builder().build().unwrap().system.is_none());
+    let Ok(system) = System::info() else {
+        return;
+    };
+    let id = system.id().to_string();
+    let app = TuiApp::builder().system(system).unwrap().build().unwrap();
+    assert_eq!(app.system.as_ref().map(System::id), Some(id.as_str()));
+}
+
+#[test]
+fn test_builder_needs_commands() {
+    let config = TuiConfig {
+        blacklist: Some(vec!["Kitty Setup".to_string()]),
+        ..TuiConfig::default()
+    };
+    assert!(TuiApp::builder().config(config).unwrap().build().is_ok());
+    let config = TuiConfig {
+        whitelist: Some(vec!["Kitty Setup".to_string()]),
+        blacklist: Some(vec!["Kitty Setup".to_string()]),
+        ..TuiConfig::default()
+    };
+    let built = TuiApp::builder().config(config).unwrap().build();
+    assert_eq!(built.err(), Some(AppError::NoCommands));
+}
@@ -0,0 +1,70 @@
+use std::{fs, io, path::PathBuf};
+
+use crate::config::{data_dir, ConfigError};
+
+/// The commands the user bookmarked, by name, in the order they were bookmarked in
+#[derive(Debug, Default)]
+pub struct BookmarkStore {
+    bookmarks: Vec<String>,
+}
+
+fn bookmarks_path() -> Option<PathBuf> {
+    data_dir().map(|dir| dir.join("favorites.json"))
+}
+
+impl BookmarkStore {
+    /// Reads `favorites.json` from the data directory, it's empty if nothing was bookmarked yet
+    pub fn load() -> Result<Self, ConfigError> {
+        let Some(path) = bookmarks_path() else {
+            return Ok(Self::default());
+        };
+        match fs::read_to_string(&path) {
+            Ok(contents) => serde_json::from_str(&contents)
+                .map(|bookmarks| Self { bookmarks })
+                .map_err(|err| ConfigError::ParseJson(path, err)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(ConfigError::Read(path, err)),
+        }
+    }
+
+    pub fn save(&self) -> Result<(), ConfigError> {
+        let Some(path) = bookmarks_path() else {
+            return Ok(());
+        };
+        let contents =
+            serde_json::to_string_pretty(&self.bookmarks).expect("strings always serialize");
+        path.parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|()| fs::write(&path, contents))
+            .map_err(|err| ConfigError::Write(path, err))
+    }
+
+    /// Bookmark the command, or remove the bookmark if it already has one
+    pub fn toggle(&mut self, name: &str) {
+        let count = self.bookmarks.len();
+        self.bookmarks.retain(|bookmark| bookmark != name);
+        if self.bookmarks.len() == count {
+            self.bookmarks.push(name.to_string());
+        }
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.bookmarks.iter().any(|bookmark| bookmark == name)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        self.bookmarks.iter().map(String::as_str)
+    }
+}
+
+#[test]
+fn test_toggle_bookmarks() {
+    let mut bookmarks = BookmarkStore::default();
+    bookmarks.toggle("Kitty Setup");
+    bookmarks.toggle("Setup Neovim");
+    assert!(bookmarks.contains("Kitty Setup"));
+
+    bookmarks.toggle("Kitty Setup");
+    assert!(!bookmarks.contains("Kitty Setup"));
+    assert_eq!(bookmarks.iter().collect::<Vec<_>>(), ["Setup Neovim"]);
+}
@@ -0,0 +1,114 @@
+use std::fmt::Write;
+
+use crate::list::{shell_quote, ListNode};
+
+/// Scripts that run one after another. Once one of them fails, the rest don't run, and the
+/// rollbacks of the failed one and the ones before it run instead, the last one first
+pub struct ScriptChain {
+    steps: Vec<ListNode>,
+}
+
+impl ScriptChain {
+    pub fn new(scripts: Vec<ListNode>) -> Self {
+        Self { steps: scripts }
+    }
+
+    /// Whether there's more to it than running a single script: more steps, or a rollback
+    pub fn is_chain(&self) -> bool {
+        self.steps.len() > 1 || self.steps.iter().any(|step| step.rollback_path().is_some())
+    }
+
+    /// A line for each step, in the order they run, for the prompt
+    pub fn describe_steps(&self) -> Vec<String> {
+        self.steps
+            .iter()
+            .enumerate()
+            .map(|(idx, step)| match step.rollback_path() {
+                Some(rollback) => format!("{}. {}, undone by {rollback}", idx + 1, step.name),
+                None => format!("{}. {}", idx + 1, step.name),
+            })
+            .collect()
+    }
+
+    /// The shell that runs the chain. Each step runs in a shell of its own, with `-e`, like the
+    /// scripts ask for. It prints which step failed and how each rollback went, and exits with 1
+    /// if a step failed. A single script without a rollback is run as it is
+    pub fn command(&self) -> String {
+        if !self.is_chain() {
+            return self
+                .steps
+                .first()
+                .map_or_else(String::new, |step| step.full_command().into_owned());
+        }
+        let total = self.steps.len();
+        let mut script = String::from("linutil_failed=0\n");
+        for (idx, step) in self.steps.iter().enumerate() {
+            let number = idx + 1;
+            let failed = format!("[linutil] Step {number} of {total}, {}, failed", step.name);
+            writeln!(
+                script,
+                "if [ \"$linutil_failed\" -eq 0 ]; then\n    sh -ec {} || {{ linutil_failed={number}; echo; echo {}; }}\nfi",
+                shell_quote(&step.full_command()),
+                shell_quote(&failed),
+            )
+            .unwrap();
+        }
+        for (idx, step) in self.steps.iter().enumerate().rev() {
+            let Some(rollback) = step.rollback_command() else {
+                continue;
+            };
+            let done = format!("[linutil] Rolled back {}", step.name);
+            let failed = format!("[linutil] Rolling back {} failed", step.name);
+            writeln!(
+                script,
+                "if [ \"$linutil_failed\" -ge {} ]; then\n    if sh -ec {}; then echo {}; else echo {}; fi\nfi",
+                idx + 1,
+                shell_quote(&rollback),
+                shell_quote(&done),
+                shell_quote(&failed),
+            )
+            .unwrap();
+        }
+        script.push_str("[ \"$linutil_failed\" -eq 0 ]\n");
+        script
+    }
+}
+
+#[test]
+fn test_chain_rolls_back() {
+    use std::process::Command;
+
+    let step = |name, command| ListNode {
+        name,
+        command,
+        script: None,
+    };
+    let single = ScriptChain::new(vec![step("Only", "echo only")]);
+    assert!(!single.is_chain());
+    assert_eq!(single.command(), "echo only");
+
+    let chain = ScriptChain::new(vec![
+        step("First", "echo first"),
+        step("Second", "echo 'second'; false; echo never"),
+        step("Third", "echo third"),
+    ]);
+    assert_eq!(chain.describe_steps()[1], "2. Second");
+    let output = Command::new("sh")
+        .arg("-c")
+        .arg(chain.command())
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(
+        stdout,
+        "first\nsecond\n\n[linutil] Step 2 of 3, Second, failed\n"
+    );
+
+    let kitty = crate::list::lookup_command("Kitty Setup").unwrap();
+    if kitty.rollback_path().is_some() {
+        let chain = ScriptChain::new(vec![kitty]);
+        assert!(chain.is_chain());
+        assert!(chain.command().contains("Rolled back Kitty Setup"));
+    }
+}
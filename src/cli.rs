@@ -0,0 +1,267 @@
+use std::{
+    io::{self, BufRead, IsTerminal, Write},
+    process::{Command, Stdio},
+    thread,
+    time::{Duration, Instant},
+};
+
+use serde::Serialize;
+
+use crate::{
+    confirmation::NonInteractiveOutput,
+    float::{FloatContent, FloatEvent},
+    list::{lookup_command, ListNode},
+    log::{ExecutionLog, LogEntry},
+    preflight,
+    running_command::{retry_delay, script_timeout, ExecutionConfig},
+};
+
+/// What the process exits with when there's no command with the name it was given, like a shell
+/// does for a command it can't find
+const NOT_FOUND: i32 = 127;
+
+/// How a command run from the command line ended
+#[derive(Debug, Serialize)]
+struct RunResult {
+    /// The name it was asked for by
+    script: String,
+    /// `None` if it wasn't found, or couldn't be started
+    exit_code: Option<i32>,
+    duration_ms: u128,
+    timed_out: bool,
+    /// Why it didn't run
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl RunResult {
+    fn failed(script: &str, error: String) -> Self {
+        Self {
+            script: script.to_string(),
+            exit_code: None,
+            duration_ms: 0,
+            timed_out: false,
+            error: Some(error),
+        }
+    }
+
+    fn succeeded(&self) -> bool {
+        self.exit_code == Some(0)
+    }
+}
+
+/// The JSON `--non-interactive` prints once everything ran
+#[derive(Debug, Serialize)]
+struct Summary {
+    results: Vec<RunResult>,
+    succeeded: usize,
+    failed: usize,
+}
+
+/// Run the command `name` refers to in the terminal linutil was started in. Returns what linutil
+/// should exit with: the exit code of the command, if it got to run
+pub fn run_script(
+    name: &str,
+    confirm: bool,
+    execution: &ExecutionConfig,
+    log: &ExecutionLog,
+) -> i32 {
+    let Some(node) = lookup_command(name) else {
+        eprintln!("There's no script called \"{name}\", --list-scripts shows all of them");
+        return NOT_FOUND;
+    };
+    if confirm && !ask_to_run(&node) {
+        return 1;
+    }
+    let result = run_node(&node, name, execution, log, || {
+        (Stdio::inherit(), Stdio::inherit())
+    });
+    if let Some(err) = &result.error {
+        eprintln!("{err}");
+    } else if result.timed_out {
+        eprintln!(
+            "{} was killed, it ran for longer than the timeout",
+            node.name
+        );
+    }
+    result.exit_code.unwrap_or(1)
+}
+
+/// Run the commands named on stdin, one per line, one after another. Empty lines and lines that
+/// start with `#` are skipped. The output of the commands goes to stderr, stdout only gets the
+/// summary, as JSON
+pub fn run_from_stdin(execution: &ExecutionConfig, log: &ExecutionLog) -> io::Result<i32> {
+    let mut results = Vec::new();
+    for line in io::stdin().lock().lines() {
+        let line = line?;
+        let name = line.trim();
+        if name.is_empty() || name.starts_with('#') {
+            continue;
+        }
+        let result = match lookup_command(name) {
+            // The commands can't have stdin, it's where the names come from
+            Some(node) => run_node(&node, name, execution, log, || {
+                (Stdio::null(), Stdio::from(io::stderr()))
+            }),
+            None => RunResult::failed(name, format!("there's no script called \"{name}\"")),
+        };
+        results.push(result);
+    }
+
+    let succeeded = results.iter().filter(|result| result.succeeded()).count();
+    let summary = Summary {
+        failed: results.len() - succeeded,
+        succeeded,
+        results,
+    };
+    println!("{}", serde_json::to_string_pretty(&summary)?);
+    Ok(if summary.failed == 0 { 0 } else { 1 })
+}
+
+/// Asks on the terminal, only a yes runs the command. Without a terminal nobody sees the question,
+/// so the first line of stdin answers it, like with `echo y | linutil --script name --confirm`
+fn ask_to_run(node: &ListNode) -> bool {
+    let interactive = io::stdin().is_terminal();
+    if interactive {
+        eprint!("Run {}? [y/N] ", node.name);
+        let _ = io::stderr().flush();
+    }
+    let mut answer = String::new();
+    if io::stdin().lock().read_line(&mut answer).is_err() {
+        return false;
+    }
+    if interactive {
+        return is_yes(&answer);
+    }
+    let mut prompt = piped_answer(&answer);
+    prompt.poll() == FloatEvent::ConfirmSelection
+}
+
+/// Answers the prompt the way the TUI does with `--yes`, only with what was piped in
+fn piped_answer(answer: &str) -> NonInteractiveOutput {
+    NonInteractiveOutput {
+        auto_confirm: is_yes(answer),
+    }
+}
+
+fn is_yes(answer: &str) -> bool {
+    matches!(answer.trim(), "y" | "Y" | "yes")
+}
+
+/// Runs the command, and waits for it to exit, or to run out of time. It's run again as many
+/// times as its `@retry` says if it fails. Every run goes to the execution log, like the ones from
+/// the TUI. `stdio` is the stdin and stdout of each attempt
+fn run_node(
+    node: &ListNode,
+    name: &str,
+    execution: &ExecutionConfig,
+    log: &ExecutionLog,
+    stdio: fn() -> (Stdio, Stdio),
+) -> RunResult {
+    if node.missing_root() {
+        return RunResult::failed(name, format!("{} only runs as root", node.name));
+    }
+    let missing = preflight::missing_commands(node.requires());
+    if !missing.is_empty() {
+        return RunResult::failed(
+            name,
+            format!(
+                "{} needs {}, they aren't on PATH",
+                node.name,
+                missing.join(", ")
+            ),
+        );
+    }
+    // There's no form to ask for them, they have to be set already
+    let unset: Vec<String> = node
+        .params()
+        .iter()
+        .map(|param| param.env_name())
+        .filter(|name| std::env::var_os(name).is_none())
+        .collect();
+    if !unset.is_empty() {
+        return RunResult::failed(
+            name,
+            format!("{} needs {} to be set", node.name, unset.join(", ")),
+        );
+    }
+    // The timeout is for all of the attempts together
+    let started = Instant::now();
+    let command = node.full_command();
+    let retries = node.retries();
+    let timeout = script_timeout([node.timeout()], execution.timeout);
+    let mut attempt = 1;
+    let (status, timed_out) = loop {
+        let (stdin, stdout) = stdio();
+        let child = Command::new("sh")
+            .arg("-c")
+            .arg(command.as_ref())
+            .envs(execution.env.iter().cloned())
+            .stdin(stdin)
+            .stdout(stdout)
+            .spawn();
+        let mut child = match child {
+            Ok(child) => child,
+            Err(err) => {
+                return RunResult::failed(name, format!("Failed to run {}: {err}", node.name))
+            }
+        };
+
+        let mut timed_out = false;
+        let status = loop {
+            match child.try_wait() {
+                Ok(Some(status)) => break Ok(status),
+                Ok(None) => {}
+                Err(err) => break Err(err),
+            }
+            if timeout.is_some_and(|timeout| started.elapsed() >= timeout) {
+                timed_out = true;
+                // It may already be gone, then there's nothing to kill
+                let _ = child.kill();
+                break child.wait();
+            }
+            thread::sleep(Duration::from_millis(50));
+        };
+        let status = match status {
+            Ok(status) => status,
+            Err(err) => {
+                return RunResult::failed(name, format!("Failed to wait for {}: {err}", node.name))
+            }
+        };
+        if status.success() || timed_out || attempt > retries {
+            break (status, timed_out);
+        }
+        attempt += 1;
+        let delay = retry_delay(attempt);
+        eprintln!(
+            "{} failed, trying again in {}s ({attempt} of {})",
+            node.name,
+            delay.as_secs(),
+            retries + 1
+        );
+        thread::sleep(delay);
+    };
+
+    let duration = started.elapsed();
+    // Killed by a signal, there's no code
+    let exit_code = status.code().unwrap_or(1);
+    let entry = LogEntry::finished_now(node.name.to_string(), exit_code as u32, duration);
+    if let Err(err) = log.append(entry) {
+        eprintln!("Failed to write the run log: {err}");
+    }
+    RunResult {
+        script: name.to_string(),
+        exit_code: Some(exit_code),
+        duration_ms: duration.as_millis(),
+        timed_out,
+        error: None,
+    }
+}
+
+#[test]
+fn test_piped_answer() {
+    assert_eq!(piped_answer("y\n").poll(), FloatEvent::ConfirmSelection);
+    assert_eq!(piped_answer("yes").poll(), FloatEvent::ConfirmSelection);
+    assert_eq!(piped_answer("n\n").poll(), FloatEvent::AbortConfirmation);
+    assert_eq!(piped_answer("").poll(), FloatEvent::AbortConfirmation);
+}
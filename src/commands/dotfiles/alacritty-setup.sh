@@ -1,4 +1,8 @@
 #!/bin/sh -e
+# @description Installs Alacritty, with Chris Titus' config
+# @tags terminal,dotfiles
+# @privilege user
+# @requires wget
 
 setupAlacritty() {
     echo "Install Alacritty if not already installed..."
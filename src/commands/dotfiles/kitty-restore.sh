@@ -0,0 +1,14 @@
+#!/bin/sh -e
+# @description Puts back the Kitty config that Kitty Setup made a backup of
+
+restoreKitty() {
+    if [ -d "${HOME}/.config/kitty-bak" ]; then
+        rm -rf "${HOME}/.config/kitty"
+        mv "${HOME}/.config/kitty-bak" "${HOME}/.config/kitty"
+        echo "Restored the Kitty config"
+    else
+        echo "There's no backup of the Kitty config to restore"
+    fi
+}
+
+restoreKitty
@@ -1,4 +1,9 @@
 #!/bin/sh -e
+# @description Installs Kitty, with Chris Titus' config
+# @tags terminal,dotfiles
+# @privilege user
+# @requires wget
+# @rollback kitty-restore.sh
 
 setupKitty() {
     echo "Install Kitty if not already installed..."
@@ -1,4 +1,8 @@
 #!/bin/sh -e
+# @description Installs Rofi, with Chris Titus' config
+# @tags launcher,dotfiles
+# @privilege user
+# @requires wget
 
 setupRofi() {
     echo "Install Rofi if not already installed..."
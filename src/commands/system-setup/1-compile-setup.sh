@@ -1,4 +1,6 @@
 #!/bin/sh -e
+# @description Installs the packages needed to build software from source
+# @tags development,packages
 
 # Check if the home directory and linuxtoolbox folder exist, create them if they don't
 LINUXTOOLBOXDIR="$HOME/linuxtoolbox"
@@ -1,4 +1,6 @@
 #!/bin/sh -e
+# @description Installs the dependencies for gaming, like Wine and Lutris
+# @tags gaming,packages
 
 installDepend() {
     ## Check for dependencies.
@@ -1,4 +1,6 @@
 #!/bin/sh -e
+# @description Installs qt6ct and Kvantum, and applies the Breeze theme
+# @tags theming
 
 # Check if the home directory and linuxtoolbox folder exist, create them if they don't
 LINUXTOOLBOXDIR="$HOME/linuxtoolbox"
@@ -1,4 +1,6 @@
 #!/bin/sh -e
+# @description Updates the system packages, and installs an AUR helper on Arch
+# @tags update,packages
 
 fastUpdate() {
     case ${PACKAGER} in
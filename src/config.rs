@@ -0,0 +1,407 @@
+use std::{env, ffi::OsString, fmt, fs, io, path::PathBuf};
+
+use crossterm::event::KeyCode;
+use serde::{de, de::DeserializeOwned, Deserialize, Deserializer, Serialize};
+
+use crate::theme::ThemeChoice;
+
+/// The directory linutil keeps its configuration in, following the XDG base directory spec
+pub fn config_dir() -> Option<PathBuf> {
+    let base = match env::var_os("XDG_CONFIG_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from(env::var_os("HOME")?).join(".config"),
+    };
+    Some(base.join("linutil"))
+}
+
+/// The directory linutil keeps the data it collects in, like the history, following the XDG base
+/// directory spec
+pub fn data_dir() -> Option<PathBuf> {
+    let base = match env::var_os("XDG_DATA_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from(env::var_os("HOME")?).join(".local/share"),
+    };
+    Some(base.join("linutil"))
+}
+
+/// Reads a TOML file from the config directory. A missing file just means the defaults
+fn load_toml<T: DeserializeOwned + Default>(file_name: &str) -> Result<T, ConfigError> {
+    match config_dir() {
+        Some(dir) => load_toml_at(dir.join(file_name)),
+        None => Ok(T::default()),
+    }
+}
+
+fn load_toml_at<T: DeserializeOwned + Default>(path: PathBuf) -> Result<T, ConfigError> {
+    match fs::read_to_string(&path) {
+        Ok(contents) => toml::from_str(&contents).map_err(|err| ConfigError::Parse(path, err)),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(T::default()),
+        Err(err) => Err(ConfigError::Read(path, err)),
+    }
+}
+
+/// The settings in `config.toml`. Settings that are left out keep their defaults
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct TuiConfig {
+    /// The theme to start with, until it's toggled. Toggling it saves the theme in `theme.toml`,
+    /// which wins over this
+    pub theme: Option<ThemeChoice>,
+    /// How much of the width the categories take, in percent. The commands get the rest
+    pub category_width: u16,
+    /// Whether the pane with the start of the selected script is open from the start
+    pub script_preview: bool,
+    /// How many lines of the script the preview pane shows
+    pub preview_height: u16,
+    /// Where the run log goes, instead of `run.log` in the data directory
+    pub log_path: Option<PathBuf>,
+    /// Kill scripts that run for longer than this many seconds. They can run for as long as they
+    /// want if it's left out
+    pub timeout: Option<u64>,
+    /// Only show these commands, by their name or the path of their script like for `--script`
+    pub whitelist: Option<Vec<String>>,
+    /// Hide these commands, even if they're in the whitelist
+    pub blacklist: Option<Vec<String>>,
+    /// Only look at the commands, nothing can be run. Dry runs still work
+    pub read_only: bool,
+}
+
+impl Default for TuiConfig {
+    fn default() -> Self {
+        Self {
+            theme: None,
+            category_width: 25,
+            script_preview: false,
+            preview_height: 20,
+            log_path: None,
+            timeout: None,
+            whitelist: None,
+            blacklist: None,
+            read_only: false,
+        }
+    }
+}
+
+impl TuiConfig {
+    /// Reads `$LINUTIL_CONFIG`, if it's set, instead of `config.toml` in the config directory
+    pub fn load() -> Result<Self, ConfigError> {
+        Self::load_from(env::var_os("LINUTIL_CONFIG"))
+    }
+
+    /// `load`, with the value of `$LINUTIL_CONFIG` passed in
+    fn load_from(env_path: Option<OsString>) -> Result<Self, ConfigError> {
+        match env_path {
+            Some(path) if !path.is_empty() => load_toml_at(PathBuf::from(path)),
+            _ => load_toml("config.toml"),
+        }
+    }
+}
+
+/// The keys for every action in the TUI. Actions that are left out of the config file keep their
+/// default keys
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct KeyBindings {
+    #[serde(deserialize_with = "deserialize_keys")]
+    pub scroll_down: Vec<KeyCode>,
+    #[serde(deserialize_with = "deserialize_keys")]
+    pub scroll_up: Vec<KeyCode>,
+    #[serde(deserialize_with = "deserialize_keys")]
+    pub page_down: Vec<KeyCode>,
+    #[serde(deserialize_with = "deserialize_keys")]
+    pub page_up: Vec<KeyCode>,
+    /// Jump to the end of the output
+    #[serde(deserialize_with = "deserialize_keys")]
+    pub scroll_to_end: Vec<KeyCode>,
+    /// Move between the categories and the commands
+    #[serde(deserialize_with = "deserialize_keys")]
+    pub switch_pane: Vec<KeyCode>,
+    /// Open a directory, or run the selected command
+    #[serde(deserialize_with = "deserialize_keys")]
+    pub open: Vec<KeyCode>,
+    /// Pick a command, to run it together with others
+    #[serde(deserialize_with = "deserialize_keys")]
+    pub pick: Vec<KeyCode>,
+    #[serde(deserialize_with = "deserialize_keys")]
+    pub preview: Vec<KeyCode>,
+    /// Bookmark the selected command, or remove its bookmark
+    #[serde(deserialize_with = "deserialize_keys")]
+    pub bookmark: Vec<KeyCode>,
+    /// Toggle dry run mode, where confirming shows what would run instead of running it
+    #[serde(deserialize_with = "deserialize_keys")]
+    pub dry_run: Vec<KeyCode>,
+    /// Toggle the pane with the start of the selected script
+    #[serde(deserialize_with = "deserialize_keys")]
+    pub script_preview: Vec<KeyCode>,
+    #[serde(deserialize_with = "deserialize_keys")]
+    pub search: Vec<KeyCode>,
+    /// Clear the search
+    #[serde(deserialize_with = "deserialize_keys")]
+    pub clear_search: Vec<KeyCode>,
+    #[serde(deserialize_with = "deserialize_keys")]
+    pub confirm: Vec<KeyCode>,
+    #[serde(deserialize_with = "deserialize_keys")]
+    pub abort: Vec<KeyCode>,
+    /// Close the log viewer, or the help
+    #[serde(deserialize_with = "deserialize_keys")]
+    pub close: Vec<KeyCode>,
+    #[serde(deserialize_with = "deserialize_keys")]
+    pub help: Vec<KeyCode>,
+    /// Toggle the shortcut overlay
+    #[serde(deserialize_with = "deserialize_keys")]
+    pub shortcuts: Vec<KeyCode>,
+    /// Show the scripts that were run before, together with Ctrl like `toggle_theme`
+    #[serde(deserialize_with = "deserialize_keys")]
+    pub log_history: Vec<KeyCode>,
+    /// Switch between the dark and the light theme. Pressed together with Ctrl, so they work while
+    /// typing in the search bar
+    #[serde(deserialize_with = "deserialize_keys")]
+    pub toggle_theme: Vec<KeyCode>,
+    #[serde(deserialize_with = "deserialize_keys")]
+    pub quit: Vec<KeyCode>,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        use KeyCode::*;
+        Self {
+            scroll_down: vec![Char('j'), Down],
+            scroll_up: vec![Char('k'), Up],
+            page_down: vec![PageDown],
+            page_up: vec![PageUp],
+            scroll_to_end: vec![Char('G')],
+            switch_pane: vec![Tab],
+            open: vec![Enter],
+            pick: vec![Char(' ')],
+            preview: vec![Char('p')],
+            bookmark: vec![Char('f')],
+            dry_run: vec![Char('d')],
+            script_preview: vec![Char('P')],
+            search: vec![Char('/')],
+            clear_search: vec![Esc],
+            confirm: vec![Char('y'), Char('Y'), Enter],
+            abort: vec![Char('n'), Char('N'), Esc],
+            close: vec![Char('q')],
+            help: vec![Char('?')],
+            shortcuts: vec![F(1)],
+            log_history: vec![Char('l')],
+            toggle_theme: vec![Char('t')],
+            quit: vec![Char('q')],
+        }
+    }
+}
+
+impl KeyBindings {
+    pub fn load() -> Result<Self, ConfigError> {
+        load_toml("keybindings.toml")
+    }
+}
+
+/// The contents of `theme.toml`
+#[derive(Debug, Serialize, Deserialize)]
+struct ThemeConfig {
+    theme: ThemeChoice,
+}
+
+fn theme_path() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join("theme.toml"))
+}
+
+/// The theme that was saved the last time it was toggled, `None` if it never was
+pub fn load_theme() -> Result<Option<ThemeChoice>, ConfigError> {
+    let Some(path) = theme_path() else {
+        return Ok(None);
+    };
+    match fs::read_to_string(&path) {
+        Ok(contents) => toml::from_str::<ThemeConfig>(&contents)
+            .map(|config| Some(config.theme))
+            .map_err(|err| ConfigError::Parse(path, err)),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(ConfigError::Read(path, err)),
+    }
+}
+
+/// Remember the theme for the next time linutil starts
+pub fn save_theme(theme: ThemeChoice) -> Result<(), ConfigError> {
+    let Some(path) = theme_path() else {
+        return Ok(());
+    };
+    let contents = toml::to_string(&ThemeConfig { theme }).expect("a lone enum always serializes");
+    path.parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|()| fs::write(&path, contents))
+        .map_err(|err| ConfigError::Write(path, err))
+}
+
+/// How the keys of an action are shown to the user, like `j/Down`
+pub fn describe(keys: &[KeyCode]) -> String {
+    let names: Vec<String> = keys.iter().map(|&key| key_name(key)).collect();
+    names.join("/")
+}
+
+/// Like `describe`, for the keys that are pressed together with Ctrl, like `Ctrl-t`
+pub fn describe_ctrl(keys: &[KeyCode]) -> String {
+    let names: Vec<String> = keys
+        .iter()
+        .map(|&key| format!("Ctrl-{}", key_name(key)))
+        .collect();
+    names.join("/")
+}
+
+/// The name of a key, the same one it's written as in the config file
+fn key_name(key: KeyCode) -> String {
+    match key {
+        KeyCode::Char(' ') => "Space".to_string(),
+        KeyCode::Char(ch) => ch.to_string(),
+        KeyCode::F(num) => format!("F{num}"),
+        KeyCode::Enter => "Enter".to_string(),
+        KeyCode::Esc => "Esc".to_string(),
+        KeyCode::Tab => "Tab".to_string(),
+        KeyCode::Backspace => "Backspace".to_string(),
+        KeyCode::Up => "Up".to_string(),
+        KeyCode::Down => "Down".to_string(),
+        KeyCode::Left => "Left".to_string(),
+        KeyCode::Right => "Right".to_string(),
+        KeyCode::PageUp => "PageUp".to_string(),
+        KeyCode::PageDown => "PageDown".to_string(),
+        KeyCode::Home => "Home".to_string(),
+        KeyCode::End => "End".to_string(),
+        KeyCode::Insert => "Insert".to_string(),
+        KeyCode::Delete => "Delete".to_string(),
+        other => format!("{other:?}"),
+    }
+}
+
+fn parse_key(name: &str) -> Option<KeyCode> {
+    let mut chars = name.chars();
+    if let (Some(ch), None) = (chars.next(), chars.next()) {
+        return Some(KeyCode::Char(ch));
+    }
+    let key = match name {
+        "Space" => KeyCode::Char(' '),
+        "Enter" => KeyCode::Enter,
+        "Esc" => KeyCode::Esc,
+        "Tab" => KeyCode::Tab,
+        "Backspace" => KeyCode::Backspace,
+        "Up" => KeyCode::Up,
+        "Down" => KeyCode::Down,
+        "Left" => KeyCode::Left,
+        "Right" => KeyCode::Right,
+        "PageUp" => KeyCode::PageUp,
+        "PageDown" => KeyCode::PageDown,
+        "Home" => KeyCode::Home,
+        "End" => KeyCode::End,
+        "Insert" => KeyCode::Insert,
+        "Delete" => KeyCode::Delete,
+        _ => KeyCode::F(name.strip_prefix('F')?.parse().ok()?),
+    };
+    Some(key)
+}
+
+fn deserialize_keys<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<KeyCode>, D::Error> {
+    let names = Vec::<String>::deserialize(deserializer)?;
+    names
+        .iter()
+        .map(|name| {
+            parse_key(name).ok_or_else(|| de::Error::custom(format!("unknown key \"{name}\"")))
+        })
+        .collect()
+}
+
+/// The reasons loading a config file can fail
+#[derive(Debug)]
+pub enum ConfigError {
+    Read(PathBuf, io::Error),
+    Parse(PathBuf, toml::de::Error),
+    ParseJson(PathBuf, serde_json::Error),
+    Write(PathBuf, io::Error),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Read(path, err) => write!(f, "could not read {}: {err}", path.display()),
+            Self::Parse(path, err) => write!(f, "invalid config in {}: {err}", path.display()),
+            Self::ParseJson(path, err) => write!(f, "invalid data in {}: {err}", path.display()),
+            Self::Write(path, err) => write!(f, "could not write {}: {err}", path.display()),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+#[test]
+fn test_key_names_round_trip() {
+    for key in KeyBindings::default().confirm.into_iter().chain([
+        KeyCode::Char(' '),
+        KeyCode::F(12),
+        KeyCode::PageDown,
+    ]) {
+        assert_eq!(parse_key(&key_name(key)), Some(key));
+    }
+    assert_eq!(parse_key("Nope"), None);
+    assert_eq!(describe(&KeyBindings::default().scroll_down), "j/Down");
+    assert_eq!(
+        describe_ctrl(&KeyBindings::default().toggle_theme),
+        "Ctrl-t"
+    );
+}
+
+#[test]
+fn test_partial_keybindings() {
+    let keys: KeyBindings = toml::from_str(
+        r#"
+        scroll_down = ["n", "Down"]
+        abort = ["Esc"]
+        "#,
+    )
+    .unwrap();
+    assert_eq!(keys.scroll_down, [KeyCode::Char('n'), KeyCode::Down]);
+    assert_eq!(keys.abort, [KeyCode::Esc]);
+    // The rest keeps its defaults
+    assert_eq!(keys.confirm, KeyBindings::default().confirm);
+
+    assert!(toml::from_str::<KeyBindings>(r#"quit = ["Hyper"]"#).is_err());
+}
+
+#[test]
+fn test_partial_tui_config() {
+    let config: TuiConfig = toml::from_str(
+        r#"
+        theme = "light"
+        log_path = "/tmp/linutil.log"
+        "#,
+    )
+    .unwrap();
+    assert_eq!(config.theme, Some(ThemeChoice::Light));
+    assert_eq!(config.log_path, Some(PathBuf::from("/tmp/linutil.log")));
+    assert_eq!(config.category_width, TuiConfig::default().category_width);
+}
+
+#[test]
+fn test_config_from_env() {
+    use crate::test_dir::TestDir;
+
+    let dir = TestDir::new("config");
+    let path = dir.join("config.toml");
+    fs::write(&path, "preview_height = 7\n").unwrap();
+    let config = TuiConfig::load_from(Some(path.clone().into())).unwrap();
+    assert_eq!(config.preview_height, 7);
+
+    fs::remove_file(&path).unwrap();
+    assert_eq!(
+        TuiConfig::load_from(Some(path.into())).unwrap(),
+        TuiConfig::default()
+    );
+}
+
+#[test]
+fn test_theme_config() {
+    let contents = toml::to_string(&ThemeConfig {
+        theme: ThemeChoice::Light,
+    })
+    .unwrap();
+    assert_eq!(contents.trim(), r#"theme = "light""#);
+    let config: ThemeConfig = toml::from_str(&contents).unwrap();
+    assert_eq!(config.theme, ThemeChoice::Light);
+}
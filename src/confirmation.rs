@@ -0,0 +1,415 @@
+use std::time::{Duration, Instant};
+
+use crossterm::event::{KeyEvent, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::{
+    layout::{Alignment, Constraint, Layout, Position, Rect},
+    style::{Style, Stylize},
+    text::Line,
+    widgets::{Clear, Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState},
+    Frame,
+};
+
+use crate::{
+    config::{describe, KeyBindings},
+    float::{FloatContent, FloatEvent},
+    hint::Shortcut,
+    theme::Theme,
+};
+
+/// What the user answered the confirmation prompt with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfirmStatus {
+    Confirm,
+    Abort,
+    /// Not answered yet
+    None,
+}
+
+/// Asks the user to confirm the commands they selected, before anything runs
+pub struct ConfirmPrompt {
+    /// The names of the commands that are about to run
+    names: Box<[Box<str>]>,
+    title: Box<str>,
+    status: ConfirmStatus,
+    /// How many names are scrolled past
+    scroll: usize,
+    /// Kept in sync with `scroll`, to show where in the list we are
+    scrollbar_state: ScrollbarState,
+    /// Where the prompt was last drawn, to know if a click landed on it
+    area: Rect,
+    /// When the prompt confirms by itself, if nobody answers it before then
+    deadline: Option<Instant>,
+}
+
+impl ConfirmPrompt {
+    pub fn new(names: &[&str]) -> Self {
+        let title = match names.len() {
+            1 => " Confirm 1 selection ".to_string(),
+            count => format!(" Confirm {count} selections "),
+        };
+        Self::with_title(names, title)
+    }
+
+    pub fn with_title(names: &[&str], title: impl Into<Box<str>>) -> Self {
+        Self {
+            names: names.iter().map(|&name| name.into()).collect(),
+            title: title.into(),
+            status: ConfirmStatus::None,
+            scroll: 0,
+            scrollbar_state: ScrollbarState::new(names.len()),
+            area: Rect::default(),
+            deadline: None,
+        }
+    }
+
+    /// A prompt that confirms by itself after `seconds`, unless it's answered earlier
+    pub fn with_timeout(names: &[&str], seconds: u8) -> Self {
+        Self {
+            deadline: Some(Instant::now() + Duration::from_secs(seconds.into())),
+            ..Self::new(names)
+        }
+    }
+
+    /// Whether the deadline of the prompt passed, if it has one
+    pub fn timed_out(&self) -> bool {
+        self.deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
+    }
+
+    pub fn scroll_down(&mut self) {
+        if self.scroll + 1 < self.names.len() {
+            self.scroll += 1;
+        }
+        self.scrollbar_state = self.scrollbar_state.position(self.scroll);
+    }
+
+    pub fn scroll_up(&mut self) {
+        self.scroll = self.scroll.saturating_sub(1);
+        self.scrollbar_state = self.scrollbar_state.position(self.scroll);
+    }
+
+    pub fn scroll_page_down(&mut self, page_size: usize) {
+        self.scroll = (self.scroll + page_size).min(self.names.len().saturating_sub(1));
+        self.scrollbar_state = self.scrollbar_state.position(self.scroll);
+    }
+
+    pub fn scroll_page_up(&mut self, page_size: usize) {
+        self.scroll = self.scroll.saturating_sub(page_size);
+        self.scrollbar_state = self.scrollbar_state.position(self.scroll);
+    }
+
+    /// How many names fit in the prompt, the last time it was drawn
+    fn page_size(&self) -> usize {
+        // Minus the borders
+        self.area.height.saturating_sub(2).max(1) as usize
+    }
+}
+
+impl FloatContent for ConfirmPrompt {
+    fn draw(&mut self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        self.area = area;
+        let title = match self.deadline {
+            Some(deadline) => {
+                // Round up, so the title doesn't show 0s while there's still time left
+                let remaining = deadline.saturating_duration_since(Instant::now());
+                let seconds = remaining.as_secs() + u64::from(remaining.subsec_nanos() > 0);
+                format!("{}({seconds}s) ", self.title)
+            }
+            None => self.title.to_string(),
+        };
+        let block = theme
+            .block()
+            .title(title)
+            .title_alignment(Alignment::Center)
+            .title_style(Style::default().fg(theme.title).bold())
+            .title_bottom(Line::from(" [y] to continue, [n] to abort ").centered())
+            .title_bottom(
+                Line::from(format!(" {}/{} ", self.scroll + 1, self.names.len())).right_aligned(),
+            );
+
+        let inner_area = block.inner(area);
+        // Don't let the list underneath show through
+        frame.render_widget(Clear, area);
+        frame.render_widget(block, area);
+
+        let [content_area, scrollbar_area] =
+            Layout::horizontal([Constraint::Min(1), Constraint::Length(1)]).areas(inner_area);
+
+        let lines: Vec<Line> = self
+            .names
+            .iter()
+            .enumerate()
+            .skip(self.scroll)
+            .map(|(idx, name)| Line::from(format!("{}. {name}", idx + 1)))
+            .collect();
+        frame.render_widget(Paragraph::new(lines), content_area);
+
+        // Size the thumb by how many names fit at once
+        self.scrollbar_state = self
+            .scrollbar_state
+            .content_length(self.names.len())
+            .viewport_content_length(content_area.height as usize)
+            .position(self.scroll);
+        let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
+            .begin_symbol(None)
+            .end_symbol(None);
+        frame.render_stateful_widget(scrollbar, scrollbar_area, &mut self.scrollbar_state);
+    }
+
+    fn handle_key_event(&mut self, key: &KeyEvent, keys: &KeyBindings) -> FloatEvent {
+        if self.timed_out() {
+            self.status = ConfirmStatus::Confirm;
+            return FloatEvent::ConfirmSelection;
+        }
+        match key.code {
+            code if keys.confirm.contains(&code) => {
+                self.status = ConfirmStatus::Confirm;
+                FloatEvent::ConfirmSelection
+            }
+            code if keys.abort.contains(&code) => {
+                self.status = ConfirmStatus::Abort;
+                FloatEvent::AbortConfirmation
+            }
+            code if keys.scroll_down.contains(&code) => {
+                self.scroll_down();
+                FloatEvent::None
+            }
+            code if keys.scroll_up.contains(&code) => {
+                self.scroll_up();
+                FloatEvent::None
+            }
+            code if keys.page_down.contains(&code) => {
+                self.scroll_page_down(self.page_size());
+                FloatEvent::None
+            }
+            code if keys.page_up.contains(&code) => {
+                self.scroll_page_up(self.page_size());
+                FloatEvent::None
+            }
+            _ => FloatEvent::None,
+        }
+    }
+
+    /// A left click on the prompt confirms, a right click aborts
+    fn handle_mouse_event(&mut self, event: &MouseEvent) -> FloatEvent {
+        if !self.area.contains(Position::new(event.column, event.row)) {
+            return FloatEvent::None;
+        }
+        match event.kind {
+            MouseEventKind::Down(MouseButton::Left) => {
+                self.status = ConfirmStatus::Confirm;
+                FloatEvent::ConfirmSelection
+            }
+            MouseEventKind::Down(MouseButton::Right) => {
+                self.status = ConfirmStatus::Abort;
+                FloatEvent::AbortConfirmation
+            }
+            MouseEventKind::ScrollDown => {
+                self.scroll_down();
+                FloatEvent::None
+            }
+            MouseEventKind::ScrollUp => {
+                self.scroll_up();
+                FloatEvent::None
+            }
+            _ => FloatEvent::None,
+        }
+    }
+
+    fn is_finished(&self) -> bool {
+        self.status != ConfirmStatus::None || self.timed_out()
+    }
+
+    fn poll(&mut self) -> FloatEvent {
+        if self.status == ConfirmStatus::None && self.timed_out() {
+            self.status = ConfirmStatus::Confirm;
+            return FloatEvent::ConfirmSelection;
+        }
+        FloatEvent::None
+    }
+
+    fn get_shortcut_list(&self, keys: &KeyBindings) -> (&str, Box<[Shortcut]>) {
+        (
+            "Confirmation prompt",
+            Box::new([
+                Shortcut::new(describe(&keys.confirm), "Run the selected commands"),
+                Shortcut::new(describe(&keys.abort), "Abort, and go back to the list"),
+                Shortcut::new(describe(&keys.scroll_down), "Scroll down"),
+                Shortcut::new(describe(&keys.scroll_up), "Scroll up"),
+                Shortcut::new(describe(&keys.page_down), "Scroll down a page"),
+                Shortcut::new(describe(&keys.page_up), "Scroll up a page"),
+                Shortcut::new("Left click", "Run the selected commands"),
+                Shortcut::new("Right click", "Abort"),
+            ]),
+        )
+    }
+
+    /// Just big enough for the names and the hints in the borders
+    fn preferred_size(&self) -> (u16, u16) {
+        let longest = self
+            .names
+            .iter()
+            .enumerate()
+            .map(|(idx, name)| Line::from(format!("{}. {name}", idx + 1)).width())
+            .max()
+            .unwrap_or(0);
+        // The borders, and the scrollbar
+        let width = (longest + 3).max(50);
+        let height = self.names.len() + 2;
+        (
+            width.try_into().unwrap_or(u16::MAX),
+            height.try_into().unwrap_or(u16::MAX),
+        )
+    }
+}
+
+/// Takes the place of the prompt when nobody is there to answer it, like with `--yes`. It answers
+/// right away, without being drawn
+pub struct NonInteractiveOutput {
+    pub auto_confirm: bool,
+}
+
+impl NonInteractiveOutput {
+    fn answer(&self) -> FloatEvent {
+        if self.auto_confirm {
+            FloatEvent::ConfirmSelection
+        } else {
+            FloatEvent::AbortConfirmation
+        }
+    }
+}
+
+impl FloatContent for NonInteractiveOutput {
+    fn draw(&mut self, _frame: &mut Frame, _area: Rect, _theme: &Theme) {}
+
+    fn handle_key_event(&mut self, _key: &KeyEvent, _keys: &KeyBindings) -> FloatEvent {
+        self.answer()
+    }
+
+    fn handle_mouse_event(&mut self, _event: &MouseEvent) -> FloatEvent {
+        self.answer()
+    }
+
+    fn is_finished(&self) -> bool {
+        true
+    }
+
+    fn poll(&mut self) -> FloatEvent {
+        self.answer()
+    }
+
+    fn get_shortcut_list(&self, _keys: &KeyBindings) -> (&str, Box<[Shortcut]>) {
+        ("Confirmation prompt", Box::new([]))
+    }
+
+    fn preferred_size(&self) -> (u16, u16) {
+        (0, 0)
+    }
+}
+
+/// Takes the place of the prompt in read-only mode, to tell that nothing can be run. Closing it
+/// aborts, like the prompt does
+pub struct ReadOnlyNotice {
+    dry_run_keys: String,
+}
+
+impl ReadOnlyNotice {
+    pub fn new(keys: &KeyBindings) -> Self {
+        Self {
+            dry_run_keys: describe(&keys.dry_run),
+        }
+    }
+}
+
+impl FloatContent for ReadOnlyNotice {
+    fn draw(&mut self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        let block = theme
+            .block()
+            .title(" Read-only mode ")
+            .title_alignment(Alignment::Center)
+            .title_style(Style::default().fg(theme.title).bold())
+            .title_bottom(Line::from(" Press any key to close ").centered());
+        let lines = vec![
+            Line::from("Running commands is disabled."),
+            Line::from(format!(
+                "[{}] toggles the dry run, to see what they would run.",
+                self.dry_run_keys
+            )),
+        ];
+        frame.render_widget(Clear, area);
+        frame.render_widget(Paragraph::new(lines).block(block), area);
+    }
+
+    fn handle_key_event(&mut self, _key: &KeyEvent, _keys: &KeyBindings) -> FloatEvent {
+        FloatEvent::AbortConfirmation
+    }
+
+    fn handle_mouse_event(&mut self, event: &MouseEvent) -> FloatEvent {
+        match event.kind {
+            MouseEventKind::Down(_) => FloatEvent::AbortConfirmation,
+            _ => FloatEvent::None,
+        }
+    }
+
+    fn is_finished(&self) -> bool {
+        false
+    }
+
+    fn get_shortcut_list(&self, _keys: &KeyBindings) -> (&str, Box<[Shortcut]>) {
+        (
+            "Read-only mode",
+            Box::new([Shortcut::new("Any key", "Close")]),
+        )
+    }
+
+    fn preferred_size(&self) -> (u16, u16) {
+        (60, 4)
+    }
+}
+
+#[test]
+fn test_non_interactive_answers() {
+    use crossterm::event::KeyCode;
+
+    let key = KeyEvent::from(KeyCode::Char('n'));
+    let mut yes = NonInteractiveOutput { auto_confirm: true };
+    assert_eq!(yes.poll(), FloatEvent::ConfirmSelection);
+    assert_eq!(
+        yes.handle_key_event(&key, &KeyBindings::default()),
+        FloatEvent::ConfirmSelection
+    );
+    let mut no = NonInteractiveOutput {
+        auto_confirm: false,
+    };
+    assert_eq!(no.poll(), FloatEvent::AbortConfirmation);
+}
+
+#[test]
+fn test_read_only_notice_aborts() {
+    use crossterm::event::KeyCode;
+
+    let keys = KeyBindings::default();
+    let mut notice = ReadOnlyNotice::new(&keys);
+    assert_eq!(notice.poll(), FloatEvent::None);
+    assert_eq!(
+        notice.handle_key_event(&KeyEvent::from(KeyCode::Char('y')), &keys),
+        FloatEvent::AbortConfirmation
+    );
+}
+
+#[test]
+fn test_timeout_confirms() {
+    use crossterm::event::KeyCode;
+
+    let mut prompt = ConfirmPrompt::new(&["Setup Neovim"]);
+    prompt.deadline = Some(Instant::now() + Duration::ZERO);
+    assert!(prompt.timed_out());
+    assert!(prompt.is_finished());
+
+    let key = KeyEvent::from(KeyCode::Char('n'));
+    assert_eq!(
+        prompt.handle_key_event(&key, &KeyBindings::default()),
+        FloatEvent::ConfirmSelection
+    );
+    assert_eq!(prompt.status, ConfirmStatus::Confirm);
+}
@@ -0,0 +1,163 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::{Alignment, Rect},
+    style::{Style, Stylize},
+    text::Line,
+    widgets::{Clear, Paragraph},
+    Frame,
+};
+use tui::systeminfo::System;
+
+use crate::{
+    config::{describe, KeyBindings},
+    float::{FloatContent, FloatEvent},
+    hint::Shortcut,
+    list::ListNode,
+    script_preview::highlight_line,
+    theme::Theme,
+};
+
+/// Shows what confirming the commands would have run, in dry run mode
+pub struct DryRunFloat {
+    /// How the commands would have been started, and on what system
+    header: Vec<String>,
+    nodes: Vec<ListNode>,
+    /// How many lines are scrolled past
+    scroll: usize,
+}
+
+impl DryRunFloat {
+    /// `system` is `None` when it couldn't be detected
+    pub fn new(nodes: &[ListNode], system: Option<&System>) -> Self {
+        let cwd = std::env::current_dir()
+            .map(|dir| dir.display().to_string())
+            .unwrap_or_else(|_| "?".to_string());
+        let system = match system {
+            Some(system) => {
+                let package_manager = match system.package_manager() {
+                    Some(package_manager) => package_manager.to_string(),
+                    None => "no package manager".to_string(),
+                };
+                format!("{}, with {package_manager}", system.pretty_name())
+            }
+            None => "an unknown system".to_string(),
+        };
+        Self {
+            header: vec![
+                format!("Would run `sh -c <the scripts below>` in {cwd}"),
+                format!("On {system}"),
+                "The helpers of common-script.sh, that start every script, are left out"
+                    .to_string(),
+            ],
+            nodes: nodes.to_vec(),
+            scroll: 0,
+        }
+    }
+
+    fn lines(&self, theme: &Theme) -> Vec<Line<'static>> {
+        let mut lines: Vec<Line> = self
+            .header
+            .iter()
+            .map(|line| Line::from(line.clone()))
+            .collect();
+        for node in &self.nodes {
+            lines.push(Line::default());
+            lines.push(Line::styled(
+                format!("{} {}", theme.cmd_icon, node.name),
+                Style::default().fg(theme.title).bold(),
+            ));
+            lines.extend(node.command.lines().map(|line| highlight_line(line, theme)));
+        }
+        lines
+    }
+
+    fn line_count(&self) -> usize {
+        let script_lines: usize = self
+            .nodes
+            .iter()
+            .map(|node| node.command.lines().count() + 2)
+            .sum();
+        self.header.len() + script_lines
+    }
+
+    fn scroll_down(&mut self) {
+        if self.scroll + 1 < self.line_count() {
+            self.scroll += 1;
+        }
+    }
+
+    fn scroll_up(&mut self) {
+        self.scroll = self.scroll.saturating_sub(1);
+    }
+}
+
+impl FloatContent for DryRunFloat {
+    fn draw(&mut self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        let block = theme
+            .block()
+            .title(" Dry run, nothing was run ")
+            .title_alignment(Alignment::Center)
+            .title_style(Style::default().fg(theme.title).bold())
+            .title_bottom(Line::from(" [j/k] to scroll, [q] to close ").centered());
+
+        let lines: Vec<Line> = self.lines(theme).into_iter().skip(self.scroll).collect();
+        frame.render_widget(Clear, area);
+        frame.render_widget(Paragraph::new(lines).block(block), area);
+    }
+
+    fn handle_key_event(&mut self, key: &KeyEvent, keys: &KeyBindings) -> FloatEvent {
+        match key.code {
+            code if keys.scroll_down.contains(&code) => self.scroll_down(),
+            code if keys.scroll_up.contains(&code) => self.scroll_up(),
+            code if keys.close.contains(&code) => return FloatEvent::CloseFloat,
+            KeyCode::Esc | KeyCode::Enter => return FloatEvent::CloseFloat,
+            _ => {}
+        }
+        FloatEvent::None
+    }
+
+    fn is_finished(&self) -> bool {
+        true
+    }
+
+    fn get_shortcut_list(&self, keys: &KeyBindings) -> (&str, Box<[Shortcut]>) {
+        let close_keys: Vec<KeyCode> = [&keys.close[..], &[KeyCode::Esc, KeyCode::Enter]].concat();
+        (
+            "Dry run",
+            Box::new([
+                Shortcut::new(describe(&keys.scroll_down), "Scroll down"),
+                Shortcut::new(describe(&keys.scroll_up), "Scroll up"),
+                Shortcut::new(describe(&close_keys), "Close the dry run"),
+            ]),
+        )
+    }
+
+    /// Scripts can get long, so take all the space there is
+    fn preferred_size(&self) -> (u16, u16) {
+        (u16::MAX, u16::MAX)
+    }
+}
+
+#[test]
+fn test_dry_run_lines() {
+    let nodes = [
+        ListNode {
+            name: "First",
+            command: "echo one\necho two",
+            script: None,
+        },
+        ListNode {
+            name: "Second",
+            command: "ls",
+            script: None,
+        },
+    ];
+    let mut dry_run = DryRunFloat::new(&nodes, None);
+    assert_eq!(dry_run.line_count(), dry_run.lines(&Theme::dark()).len());
+    assert!(dry_run.header[1].contains("unknown system"));
+
+    for _ in 0..20 {
+        dry_run.handle_key_event(&KeyEvent::from(KeyCode::Char('j')), &KeyBindings::default());
+    }
+    assert_eq!(dry_run.scroll, dry_run.line_count() - 1);
+}
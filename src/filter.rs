@@ -0,0 +1,83 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{layout::Rect, widgets::Paragraph, Frame};
+
+use crate::theme::Theme;
+
+/// An inline search bar, that narrows the list down to the commands whose names contain the typed
+/// text
+pub struct FilterBar {
+    /// The text typed so far
+    input: String,
+    /// Whether key presses go to the filter bar instead of the list
+    focused: bool,
+}
+
+impl FilterBar {
+    pub fn new() -> Self {
+        Self {
+            input: String::new(),
+            focused: false,
+        }
+    }
+
+    /// Start sending key presses to the filter bar
+    pub fn focus(&mut self) {
+        self.focused = true;
+    }
+
+    pub fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    /// The bar is shown while typing, and afterwards for as long as it is filtering the list
+    pub fn is_shown(&self) -> bool {
+        self.focused || self.is_filtering()
+    }
+
+    pub fn is_filtering(&self) -> bool {
+        !self.input.is_empty()
+    }
+
+    /// Forget the filter, restoring the full list
+    pub fn clear(&mut self) {
+        self.input.clear();
+        self.focused = false;
+    }
+
+    /// Case insensitive substring match against the typed text
+    pub fn matches(&self, name: &str) -> bool {
+        name.to_lowercase().contains(&self.input.to_lowercase())
+    }
+
+    /// Handle a key press while focused. Returns `true` if the filter text changed
+    pub fn handle_key(&mut self, event: KeyEvent) -> bool {
+        match event.code {
+            KeyCode::Char(ch) => {
+                self.input.push(ch);
+                true
+            }
+            KeyCode::Backspace => self.input.pop().is_some(),
+            // Esc throws the filter away, Enter keeps it, so the results can be navigated
+            KeyCode::Esc => {
+                let changed = self.is_filtering();
+                self.clear();
+                changed
+            }
+            KeyCode::Enter => {
+                self.focused = false;
+                false
+            }
+            _ => false,
+        }
+    }
+
+    pub fn draw(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        let text = format!("/{}", self.input);
+        if self.focused {
+            // Put the cursor after the text, like in a regular input field
+            frame.set_cursor(area.x + 1 + text.chars().count() as u16, area.y + 1);
+        }
+        let bar = Paragraph::new(text).block(theme.block().title("Search"));
+        frame.render_widget(bar, area);
+    }
+}
@@ -1,4 +1,54 @@
-use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use crossterm::event::{KeyEvent, MouseEvent};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    Frame,
+};
+
+use crate::{config::KeyBindings, hint::Shortcut, theme::Theme};
+
+/// Something that can be shown in a floating window on top of the command list, like the
+/// confirmation prompt, or a running command
+pub trait FloatContent {
+    /// Draw the content into the area of the floating window
+    fn draw(&mut self, frame: &mut Frame, area: Rect, theme: &Theme);
+    /// Handle a key press, and tell the main loop what should happen because of it
+    fn handle_key_event(&mut self, key: &KeyEvent, keys: &KeyBindings) -> FloatEvent;
+    /// Handle a mouse event, floats that don't care about the mouse can just ignore it
+    fn handle_mouse_event(&mut self, _event: &MouseEvent) -> FloatEvent {
+        FloatEvent::None
+    }
+    /// Whether the content is done, and has nothing more to show
+    fn is_finished(&self) -> bool;
+    /// Checked whenever there are no events, for floats that can act by themselves, like a prompt
+    /// that runs out of time
+    fn poll(&mut self) -> FloatEvent {
+        FloatEvent::None
+    }
+    /// The name of the float, and the keys it reacts to, for the help
+    fn get_shortcut_list(&self, keys: &KeyBindings) -> (&str, Box<[Shortcut]>);
+    /// How big the float would like to be, as `(width, height)` in cells. It never gets more
+    /// than the floating window has to offer
+    fn preferred_size(&self) -> (u16, u16) {
+        (40, 20)
+    }
+}
+
+/// What the main loop should do after a float handled an event
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FloatEvent {
+    /// Nothing, the float handled it by itself
+    None,
+    /// The float should be closed
+    CloseFloat,
+    /// The user confirmed the prompt, the selected commands should be run
+    ConfirmSelection,
+    /// The user aborted the prompt, nothing should be run
+    AbortConfirmation,
+    /// The user wants to see the whole output of the running command
+    ShowLog,
+    /// The terminal was resized, so the float should be laid out again
+    Resize,
+}
 
 /// This function just makes a given area smaller by 20 % in each direction, creating a kind of
 /// "floating window". And you don't actually need all the constraints, and layouts to do that, its
@@ -26,6 +76,21 @@ pub fn floating_window(size: Rect) -> Rect {
         .split(hor_float)[1]
 }
 
+/// Where the float should be drawn: its preferred size, centered in the floating window, and
+/// shrunk to fit when the window is smaller than that
+pub fn float_area(float: &dyn FloatContent, size: Rect) -> Rect {
+    let available = floating_window(size);
+    let (width, height) = float.preferred_size();
+    let width = width.min(available.width);
+    let height = height.min(available.height);
+    Rect {
+        x: available.x + (available.width - width) / 2,
+        y: available.y + (available.height - height) / 2,
+        width,
+        height,
+    }
+}
+
 /// Here is how would a purely math based function look like:
 /// But it might break on smaller numbers
 fn _unused_manual_floating_window(size: Rect) -> Rect {
@@ -57,3 +122,38 @@ fn test_floating() {
     let res2 = floating_window(rect);
     assert_eq!(res1, res2);
 }
+
+#[cfg(test)]
+struct SizedFloat(u16, u16);
+
+#[cfg(test)]
+impl FloatContent for SizedFloat {
+    fn draw(&mut self, _frame: &mut Frame, _area: Rect, _theme: &Theme) {}
+    fn handle_key_event(&mut self, _key: &KeyEvent, _keys: &KeyBindings) -> FloatEvent {
+        FloatEvent::None
+    }
+    fn is_finished(&self) -> bool {
+        false
+    }
+    fn get_shortcut_list(&self, _keys: &KeyBindings) -> (&str, Box<[Shortcut]>) {
+        ("Sized float", Box::new([]))
+    }
+    fn preferred_size(&self) -> (u16, u16) {
+        (self.0, self.1)
+    }
+}
+
+#[test]
+fn test_float_area() {
+    let size = Rect::new(0, 0, 100, 50);
+    let available = floating_window(size);
+
+    // Small floats are centered in the floating window
+    let area = float_area(&SizedFloat(20, 10), size);
+    assert_eq!((area.width, area.height), (20, 10));
+    assert_eq!(area.x, available.x + (available.width - 20) / 2);
+    assert_eq!(area.y, available.y + (available.height - 10) / 2);
+
+    // Big ones are clamped to it
+    assert_eq!(float_area(&SizedFloat(u16::MAX, u16::MAX), size), available);
+}
@@ -0,0 +1,185 @@
+use ratatui::{layout::Rect, Frame};
+
+use crate::{
+    config::KeyBindings,
+    dry_run::DryRunFloat,
+    float::{float_area, FloatContent},
+    help::HelpFloat,
+    hint::Shortcut,
+    list::ListNode,
+    log_history::LogHistoryFloat,
+    log_viewer::LogViewerFloat,
+    post_run::PostRunFloat,
+    preflight::MissingDepsFloat,
+    running_command::RunningCommand,
+    script_params::ScriptParamFloat,
+    theme::Theme,
+};
+
+/// One of the floats on top of the list, with what the main loop needs to know about it
+pub enum Float {
+    /// The scripts that were run before
+    RunLog(LogHistoryFloat),
+    /// The prompt, together with the commands it is asking about
+    Prompt(Box<dyn FloatContent>, Vec<ListNode>),
+    /// What the picked commands need, but isn't there
+    MissingDeps(MissingDepsFloat),
+    /// Asks for the values the picked scripts need, and runs them with those
+    Params(ScriptParamFloat, Vec<ListNode>),
+    /// What the confirmed commands would have run, in dry run mode
+    DryRun(DryRunFloat),
+    Command(RunningCommand),
+    /// How the last commands went, it takes the place of the command once it's finished
+    PostRun(PostRunFloat),
+    /// The whole output of the command below it
+    Log(LogViewerFloat),
+    Help(HelpFloat),
+}
+
+impl Float {
+    pub fn content(&self) -> &dyn FloatContent {
+        match self {
+            Self::RunLog(float) => float,
+            Self::Prompt(float, _) => float.as_ref(),
+            Self::MissingDeps(float) => float,
+            Self::Params(float, _) => float,
+            Self::DryRun(float) => float,
+            Self::Command(float) => float,
+            Self::PostRun(float) => float,
+            Self::Log(float) => float,
+            Self::Help(float) => float,
+        }
+    }
+
+    pub fn content_mut(&mut self) -> &mut dyn FloatContent {
+        match self {
+            Self::RunLog(float) => float,
+            Self::Prompt(float, _) => float.as_mut(),
+            Self::MissingDeps(float) => float,
+            Self::Params(float, _) => float,
+            Self::DryRun(float) => float,
+            Self::Command(float) => float,
+            Self::PostRun(float) => float,
+            Self::Log(float) => float,
+            Self::Help(float) => float,
+        }
+    }
+}
+
+/// The floats on top of the list. The last one is drawn on top of the others, and gets the events
+#[derive(Default)]
+pub struct FloatStack {
+    floats: Vec<Float>,
+}
+
+impl FloatStack {
+    pub fn push(&mut self, float: Float) {
+        self.floats.push(float);
+    }
+
+    pub fn pop(&mut self) -> Option<Float> {
+        self.floats.pop()
+    }
+
+    pub fn top(&self) -> Option<&Float> {
+        self.floats.last()
+    }
+
+    pub fn top_mut(&mut self) -> Option<&mut Float> {
+        self.floats.last_mut()
+    }
+
+    /// The command, wherever it is in the stack
+    pub fn command(&self) -> Option<&RunningCommand> {
+        self.floats.iter().find_map(|float| match float {
+            Float::Command(command) => Some(command),
+            _ => None,
+        })
+    }
+
+    pub fn command_mut(&mut self) -> Option<&mut RunningCommand> {
+        self.floats.iter_mut().find_map(|float| match float {
+            Float::Command(command) => Some(command),
+            _ => None,
+        })
+    }
+
+    /// Puts `float` where the command is, so whatever is on top of it stays there
+    pub fn replace_command(&mut self, float: Float) {
+        if let Some(command) = self
+            .floats
+            .iter_mut()
+            .find(|float| matches!(float, Float::Command(_)))
+        {
+            *command = float;
+        }
+    }
+
+    /// Pushes the lines the command printed since the last time to the log on top of it
+    pub fn sync_log(&mut self) {
+        let (mut command, mut log) = (None, None);
+        for float in &mut self.floats {
+            match float {
+                Float::Command(float) => command = Some(float),
+                Float::Log(float) => log = Some(float),
+                _ => {}
+            }
+        }
+        let (Some(command), Some(log)) = (command, log) else {
+            return;
+        };
+        if log.is_finished() {
+            return;
+        }
+        // Check before reading the output, so no lines can be missed in between
+        let exited = command.is_finished();
+        let lines = command.output_lines();
+        for line in lines.into_iter().skip(log.line_count()) {
+            log.push_line(line);
+        }
+        if exited {
+            log.mark_exited();
+        }
+    }
+
+    /// Bottom first
+    pub fn draw(&mut self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        for float in &mut self.floats {
+            let content = float.content_mut();
+            content.draw(frame, float_area(content, area), theme);
+        }
+    }
+
+    /// The shortcuts of every float, bottom first, for the help
+    pub fn shortcut_lists(&self, keys: &KeyBindings) -> Vec<(&str, Box<[Shortcut]>)> {
+        self.floats
+            .iter()
+            .map(|float| float.content().get_shortcut_list(keys))
+            .collect()
+    }
+}
+
+#[test]
+fn test_command_is_replaced_under_the_log() {
+    use crate::running_command::ExecutionConfig;
+
+    let mut floats = FloatStack::default();
+    floats.push(Float::Command(RunningCommand::new(
+        &["true"],
+        &ExecutionConfig::default(),
+    )));
+    floats.push(Float::Log(LogViewerFloat::default()));
+    assert!(floats.command().is_some());
+
+    floats.replace_command(Float::PostRun(PostRunFloat::new(
+        "true".to_string(),
+        0,
+        std::time::Duration::ZERO,
+        false,
+        Vec::new(),
+    )));
+    assert!(floats.command().is_none());
+    assert!(matches!(floats.pop(), Some(Float::Log(_))));
+    assert!(matches!(floats.pop(), Some(Float::PostRun(_))));
+    assert!(floats.top().is_none());
+}
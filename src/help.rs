@@ -0,0 +1,133 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::{Alignment, Constraint, Rect},
+    style::{Style, Stylize},
+    text::Line,
+    widgets::{Clear, Row, Table},
+    Frame,
+};
+
+use crate::{
+    config::{describe, KeyBindings},
+    float::{FloatContent, FloatEvent},
+    hint::Shortcut,
+    theme::Theme,
+};
+
+/// Lists the shortcuts of everything that is on the screen, grouped by the widget they belong to
+pub struct HelpFloat {
+    shortcuts: Vec<(Box<str>, Box<[Shortcut]>)>,
+    /// How many rows are scrolled past
+    scroll: usize,
+}
+
+impl HelpFloat {
+    pub fn new(shortcuts: Vec<(&str, Box<[Shortcut]>)>) -> Self {
+        Self {
+            shortcuts: shortcuts
+                .into_iter()
+                .map(|(name, shortcuts)| (name.into(), shortcuts))
+                .collect(),
+            scroll: 0,
+        }
+    }
+
+    /// Every group is a header row, followed by a row for each shortcut
+    fn row_count(&self) -> usize {
+        self.shortcuts
+            .iter()
+            .map(|(_, shortcuts)| shortcuts.len() + 1)
+            .sum()
+    }
+
+    fn scroll_down(&mut self) {
+        if self.scroll + 1 < self.row_count() {
+            self.scroll += 1;
+        }
+    }
+
+    fn scroll_up(&mut self) {
+        self.scroll = self.scroll.saturating_sub(1);
+    }
+}
+
+impl FloatContent for HelpFloat {
+    fn draw(&mut self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        let block = theme
+            .block()
+            .title(" Help ")
+            .title_alignment(Alignment::Center)
+            .title_style(Style::default().fg(theme.title).bold())
+            .title_bottom(Line::from(" [j/k] to scroll, [q] to close ").centered());
+
+        let rows = self.shortcuts.iter().flat_map(|(name, shortcuts)| {
+            let header = Row::new([name.to_string()]).style(Style::default().bold());
+            let shortcuts = shortcuts.iter().map(|shortcut| {
+                Row::new([format!("  {}", shortcut.key), shortcut.action.into()])
+                    .style(shortcut.style())
+            });
+            std::iter::once(header).chain(shortcuts)
+        });
+        let table = Table::new(
+            rows.skip(self.scroll),
+            [Constraint::Length(16), Constraint::Min(1)],
+        )
+        .block(block);
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(table, area);
+    }
+
+    fn handle_key_event(&mut self, key: &KeyEvent, keys: &KeyBindings) -> FloatEvent {
+        match key.code {
+            code if keys.scroll_down.contains(&code) => self.scroll_down(),
+            code if keys.scroll_up.contains(&code) => self.scroll_up(),
+            // The key that opened the help closes it too
+            code if keys.close.contains(&code) || keys.help.contains(&code) => {
+                return FloatEvent::CloseFloat
+            }
+            KeyCode::Esc => return FloatEvent::CloseFloat,
+            _ => {}
+        }
+        FloatEvent::None
+    }
+
+    fn is_finished(&self) -> bool {
+        false
+    }
+
+    fn get_shortcut_list(&self, keys: &KeyBindings) -> (&str, Box<[Shortcut]>) {
+        let close_keys: Vec<KeyCode> = [&keys.close[..], &keys.help, &[KeyCode::Esc]].concat();
+        (
+            "Help",
+            Box::new([
+                Shortcut::new(describe(&keys.scroll_down), "Scroll down"),
+                Shortcut::new(describe(&keys.scroll_up), "Scroll up"),
+                Shortcut::new(describe(&close_keys), "Close the help"),
+            ]),
+        )
+    }
+
+    fn preferred_size(&self) -> (u16, u16) {
+        // The borders, too
+        let height = self.row_count() + 2;
+        (60, height.try_into().unwrap_or(u16::MAX))
+    }
+}
+
+#[test]
+fn test_help_scroll() {
+    let mut help = HelpFloat::new(vec![
+        (
+            "List",
+            Box::new([Shortcut::new("j", "Down"), Shortcut::new("k", "Up")]),
+        ),
+        ("Help", Box::new([Shortcut::new("q", "Close")])),
+    ]);
+    assert_eq!(help.row_count(), 5);
+
+    for _ in 0..10 {
+        help.handle_key_event(&KeyEvent::from(KeyCode::Char('j')), &KeyBindings::default());
+    }
+    assert_eq!(help.scroll, 4);
+}
@@ -0,0 +1,129 @@
+use ratatui::{
+    layout::Rect,
+    style::{Style, Stylize},
+    text::{Line, Span},
+    widgets::{Clear, Paragraph},
+    Frame,
+};
+
+use crate::theme::Theme;
+
+/// A key, and what it does. Widgets list these, so they can be shown to the user
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Shortcut {
+    /// How the key is shown, like `j/Down`
+    pub key: String,
+    pub action: &'static str,
+    /// Shown dimmed, the key does nothing at the moment
+    pub disabled: bool,
+}
+
+impl Shortcut {
+    pub fn new(key: impl Into<String>, action: &'static str) -> Self {
+        Self {
+            key: key.into(),
+            action,
+            disabled: false,
+        }
+    }
+
+    /// Dim the shortcut if `disabled` is true
+    pub fn disabled_if(self, disabled: bool) -> Self {
+        Self { disabled, ..self }
+    }
+
+    pub fn style(&self) -> Style {
+        if self.disabled {
+            Style::default().dim()
+        } else {
+            Style::default()
+        }
+    }
+}
+
+/// A box at the bottom of the screen with the shortcuts of whatever has the focus. It only takes
+/// up space while it's shown, and doesn't take any input itself
+pub struct ShortcutOverlay {
+    shown: bool,
+}
+
+impl ShortcutOverlay {
+    pub fn new() -> Self {
+        Self { shown: false }
+    }
+
+    pub fn toggle(&mut self) {
+        self.shown = !self.shown;
+    }
+
+    pub fn draw(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        theme: &Theme,
+        shortcut_list: (&str, Box<[Shortcut]>),
+    ) {
+        if !self.shown {
+            return;
+        }
+        let (name, shortcuts) = shortcut_list;
+        // The borders, on both sides
+        let lines = wrap_shortcuts(&shortcuts, area.width.saturating_sub(2));
+        let height = (lines.len() as u16 + 2).min(area.height);
+        let overlay_area = Rect {
+            y: area.bottom() - height,
+            height,
+            ..area
+        };
+
+        let block = theme
+            .block()
+            .title(format!(" {name} "))
+            .title_style(Style::default().fg(theme.title).bold());
+        frame.render_widget(Clear, overlay_area);
+        frame.render_widget(Paragraph::new(lines).block(block), overlay_area);
+    }
+}
+
+/// Put as many shortcuts on a line as fit in `width`
+fn wrap_shortcuts(shortcuts: &[Shortcut], width: u16) -> Vec<Line<'static>> {
+    const SEPARATOR: &str = "  ";
+    let mut lines = Vec::new();
+    let mut spans: Vec<Span> = Vec::new();
+    let mut line_width = 0;
+    for shortcut in shortcuts {
+        let shortcut_width = shortcut.key.len() + 1 + shortcut.action.len();
+        if line_width > 0 && line_width + SEPARATOR.len() + shortcut_width > width as usize {
+            lines.push(Line::from(std::mem::take(&mut spans)));
+            line_width = 0;
+        }
+        if line_width > 0 {
+            spans.push(Span::raw(SEPARATOR));
+            line_width += SEPARATOR.len();
+        }
+        spans.push(Span::styled(shortcut.key.clone(), shortcut.style().bold()));
+        spans.push(Span::styled(
+            format!(" {}", shortcut.action),
+            shortcut.style(),
+        ));
+        line_width += shortcut_width;
+    }
+    if !spans.is_empty() {
+        lines.push(Line::from(spans));
+    }
+    lines
+}
+
+#[test]
+fn test_wrap_shortcuts() {
+    let shortcuts = [
+        Shortcut::new("j", "Down"),
+        Shortcut::new("k", "Up"),
+        Shortcut::new("q", "Quit"),
+    ];
+    // "j Down  k Up" is 12 wide, the quit doesn't fit next to it anymore
+    let lines = wrap_shortcuts(&shortcuts, 15);
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0].width(), 12);
+    assert_eq!(wrap_shortcuts(&shortcuts, 80).len(), 1);
+}
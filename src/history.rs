@@ -0,0 +1,71 @@
+use std::{collections::VecDeque, fs, io, path::PathBuf};
+
+use crate::config::{data_dir, ConfigError};
+
+/// How many commands the history remembers
+const HISTORY_LEN: usize = 10;
+
+/// The names of the commands that were run last, the most recent one first
+#[derive(Debug, Default)]
+pub struct History {
+    recently_run: VecDeque<String>,
+}
+
+fn history_path() -> Option<PathBuf> {
+    data_dir().map(|dir| dir.join("history.json"))
+}
+
+impl History {
+    /// Reads `history.json` from the data directory, it's empty if nothing was run yet
+    pub fn load() -> Result<Self, ConfigError> {
+        let Some(path) = history_path() else {
+            return Ok(Self::default());
+        };
+        match fs::read_to_string(&path) {
+            Ok(contents) => serde_json::from_str(&contents)
+                .map(|recently_run| Self { recently_run })
+                .map_err(|err| ConfigError::ParseJson(path, err)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(ConfigError::Read(path, err)),
+        }
+    }
+
+    pub fn save(&self) -> Result<(), ConfigError> {
+        let Some(path) = history_path() else {
+            return Ok(());
+        };
+        let contents =
+            serde_json::to_string_pretty(&self.recently_run).expect("strings always serialize");
+        path.parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|()| fs::write(&path, contents))
+            .map_err(|err| ConfigError::Write(path, err))
+    }
+
+    /// Remember that a command was run. Running it again moves it to the front, instead of
+    /// adding it twice
+    pub fn push(&mut self, name: &str) {
+        self.recently_run.retain(|recent| recent != name);
+        self.recently_run.push_front(name.to_string());
+        self.recently_run.truncate(HISTORY_LEN);
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        self.recently_run.iter().map(String::as_str)
+    }
+}
+
+#[test]
+fn test_history_push() {
+    let mut history = History::default();
+    for idx in 0..12 {
+        history.push(&format!("command {idx}"));
+    }
+    history.push("command 5");
+
+    let names: Vec<&str> = history.iter().collect();
+    assert_eq!(names.len(), HISTORY_LEN);
+    assert_eq!(names[..3], ["command 5", "command 11", "command 10"]);
+    // The oldest ones are gone
+    assert!(!names.contains(&"command 1"));
+}
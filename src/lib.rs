@@ -0,0 +1,5 @@
+//! System detection shared by the TUI and anything else that wants to know what it's running on
+pub mod packagemanagers;
+pub mod systeminfo;
+#[cfg(test)]
+mod test_dir;
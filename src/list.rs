@@ -1,26 +1,128 @@
-use crate::{float::floating_window, theme::*};
-use crossterm::event::{KeyCode, KeyEvent, KeyEventKind};
-use ego_tree::{tree, NodeId};
+use std::{borrow::Cow, collections::HashSet, path::Path, time::Duration};
+
+use crate::{
+    bookmarks::BookmarkStore,
+    config::{describe, describe_ctrl, ConfigError, KeyBindings, TuiConfig},
+    filter::FilterBar,
+    float::floating_window,
+    hint::Shortcut,
+    history::History,
+    metadata::{self, PrivilegeMode, ScriptParam},
+    script_preview,
+    theme::*,
+};
+use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, MouseButton, MouseEvent, MouseEventKind};
+use ego_tree::{tree, NodeId, Tree};
 use ratatui::{
-    layout::Rect,
-    style::{Style, Stylize},
+    layout::{Constraint, Layout, Margin, Position, Rect},
+    style::Style,
     text::Line,
-    widgets::{Block, Borders, List, ListState},
+    widgets::{Block, List, ListState},
     Frame,
 };
 
-macro_rules! with_common_script {
-    ($command:expr) => {
-        concat!(
-            include_str!("commands/common-script.sh"),
-            include_str!($command)
-        )
+/// The helpers every script in `src/commands` starts with. It's only embedded once, and put in
+/// front of the scripts when they're run
+const COMMON_SCRIPT: &str = include_str!("commands/common-script.sh");
+
+/// A node of the tree for one of the scripts in `src/commands`, `$path` is relative to it
+macro_rules! script_node {
+    ($name:expr, $path:expr) => {
+        ListNode {
+            name: $name,
+            command: include_str!(concat!("commands/", $path)),
+            script: Some($path),
+        }
     };
 }
 
-struct ListNode {
-    name: &'static str,
-    command: &'static str,
+#[derive(Clone, Copy)]
+pub struct ListNode {
+    pub name: &'static str,
+    /// For scripts, this is without the common script
+    pub command: &'static str,
+    /// The path of the script in `src/commands`, to find its metadata. `None` for directories,
+    /// and commands that aren't scripts of their own
+    pub script: Option<&'static str>,
+}
+
+impl ListNode {
+    /// What actually gets run, scripts need the common script in front of them. Those that run
+    /// with sudo get it in front of that, unless linutil already runs as root
+    pub fn full_command(&self) -> Cow<'static, str> {
+        let command = match self.script {
+            Some(_) => Cow::Owned(format!("{COMMON_SCRIPT}{}", self.command)),
+            None => Cow::Borrowed(self.command),
+        };
+        with_privilege(command, self.privilege(), running_as_root())
+    }
+
+    /// What undoes the command, with the common script in front of it. It runs with the same
+    /// privilege as the command
+    pub fn rollback_command(&self) -> Option<Cow<'static, str>> {
+        let rollback = self.script.and_then(metadata::find)?.rollback.as_ref()?;
+        let command = Cow::Owned(format!("{COMMON_SCRIPT}{}", rollback.command));
+        Some(with_privilege(command, self.privilege(), running_as_root()))
+    }
+
+    /// The path of the script that undoes the command, relative to `src/commands`
+    pub fn rollback_path(&self) -> Option<&'static str> {
+        let rollback = self.script.and_then(metadata::find)?.rollback.as_ref()?;
+        Some(rollback.path)
+    }
+
+    /// What the metadata of the script says, commands that aren't scripts run as the user
+    pub fn privilege(&self) -> PrivilegeMode {
+        self.script
+            .and_then(metadata::find)
+            .map_or_else(PrivilegeMode::default, |meta| meta.privilege)
+    }
+
+    /// The commands the metadata of the script says it needs
+    pub fn requires(&self) -> &'static [&'static str] {
+        self.script
+            .and_then(metadata::find)
+            .map_or(&[], |meta| meta.requires)
+    }
+
+    /// The values the metadata of the script says it asks for
+    pub fn params(&self) -> &'static [ScriptParam] {
+        self.script
+            .and_then(metadata::find)
+            .map_or(&[], |meta| meta.params)
+    }
+
+    /// How many more times the command is run if it fails
+    pub fn retries(&self) -> u32 {
+        self.script
+            .and_then(metadata::find)
+            .map_or(0, |meta| meta.retry)
+    }
+
+    /// How long the command may run for, if its `@timeout` says
+    pub fn timeout(&self) -> Option<Duration> {
+        self.script
+            .and_then(metadata::find)
+            .and_then(|meta| meta.timeout)
+            .map(Duration::from_secs)
+    }
+
+    /// Whether the command only runs as root, and linutil doesn't
+    pub fn missing_root(&self) -> bool {
+        self.privilege() == PrivilegeMode::RequireSudo && !running_as_root()
+    }
+
+    /// Whether `name` refers to this command: its name in the list, the path of its script in
+    /// `src/commands`, or that path without `.sh`, or just the name of the file without it
+    fn is_called(&self, name: &str) -> bool {
+        self.name.eq_ignore_ascii_case(name)
+            || self.script.is_some_and(|path| {
+                let path = Path::new(path);
+                path == Path::new(name)
+                    || path.with_extension("") == Path::new(name)
+                    || path.file_stem().is_some_and(|stem| stem == name)
+            })
+    }
 }
 
 /// This is a data structure that has everything necessary to draw and manage a menu of commands
@@ -37,6 +139,55 @@ pub struct CustomList {
     /// This stores the preview windows state. If it is None, it will not be displayed.
     /// If it is Some, we show it with the content of the selected item
     preview_window_state: Option<PreviewWindowState>,
+    /// The search bar, while it has any text the list shows the matching commands from the whole
+    /// tree, instead of the current directory
+    filter: FilterBar,
+    /// The commands picked with <Space>, to be run together
+    selected_commands: HashSet<NodeId>,
+    /// Where the list was last drawn, used to find out which item was clicked
+    list_area: Rect,
+    /// The categories in the left pane, the commands of the selected one are shown on the right
+    categories: Vec<Category>,
+    category_state: ListState,
+    /// Where the categories were last drawn, used to find out which one was clicked
+    category_area: Rect,
+    /// Which pane gets the keys
+    focus: Pane,
+    /// How much of the width the categories take, in percent
+    category_width: u16,
+    /// Nothing can be run, so the shortcuts for running are dimmed
+    read_only: bool,
+    /// Whether the start of the selected script is shown under the commands
+    script_preview_shown: bool,
+    /// How many lines of the script the preview shows
+    script_preview_height: u16,
+    /// The commands run last, for the recent category
+    history: History,
+    /// The commands for the favorites category
+    bookmarks: BookmarkStore,
+    /// Set when the bookmarks couldn't be saved, until the main loop takes it
+    bookmark_error: Option<ConfigError>,
+}
+
+/// One of the categories the commands can be narrowed down to
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Category {
+    /// The commands that were run last
+    Recent,
+    /// The bookmarked commands
+    Favorites,
+    /// The whole tree
+    All,
+    /// A directory at the top of the tree
+    Directory(NodeId),
+    /// The scripts with this tag in their metadata
+    Tag(&'static str),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Pane {
+    Categories,
+    Commands,
 }
 
 /// This struct stores the preview window state
@@ -54,100 +205,234 @@ impl PreviewWindowState {
     }
 }
 
-impl CustomList {
-    pub fn new() -> Self {
-        // When a function call ends with an exclamation mark, it means it's a macro, like in this
-        // case the tree! macro expands to `ego-tree::tree` data structure
-        let tree = tree!(ListNode {
-            name: "root",
-            command: ""
+/// Every command there is, directories have the commands in them as their children
+fn command_tree() -> Tree<ListNode> {
+    // When a function call ends with an exclamation mark, it means it's a macro, like in this
+    // case the tree! macro expands to `ego-tree::tree` data structure
+    tree!(ListNode {
+        name: "root",
+        command: "",
+        script: None
+    } => {
+        script_node!("Full System Update", "system-update.sh"),
+        ListNode {
+            name: "Setup Bash Prompt",
+            command: "bash -c \"$(curl -s https://raw.githubusercontent.com/ChrisTitusTech/mybash/main/setup.sh)\"",
+            script: None
+        },
+        ListNode {
+            name: "Setup Neovim",
+            command: "bash -c \"$(curl -s https://raw.githubusercontent.com/ChrisTitusTech/neovim/main/setup.sh)\"",
+            script: None
+        },
+        // ListNode {
+        //     name: "Just ls, nothing special, trust me",
+        //     command: include_str!("commands/special_ls.sh"),
+        // },
+        ListNode {
+            name: "System Setup",
+            command: "",
+            script: None
         } => {
+            script_node!("Build Prerequisites", "system-setup/1-compile-setup.sh"),
+            script_node!("Gaming Dependencies", "system-setup/2-gaming-setup.sh"),
+            script_node!("Global Theme", "system-setup/3-global-theme.sh"),
             ListNode {
-                name: "Full System Update",
-                command: with_common_script!("commands/system-update.sh"),
-            },
-            ListNode {
-                name: "Setup Bash Prompt",
-                command: "bash -c \"$(curl -s https://raw.githubusercontent.com/ChrisTitusTech/mybash/main/setup.sh)\""
-            },
-            ListNode {
-                name: "Setup Neovim",
-                command: "bash -c \"$(curl -s https://raw.githubusercontent.com/ChrisTitusTech/neovim/main/setup.sh)\""
-            },
-            // ListNode {
-            //     name: "Just ls, nothing special, trust me",
-            //     command: include_str!("commands/special_ls.sh"),
-            // },
-            ListNode {
-                name: "System Setup",
-                command: ""
-            } => {
-                ListNode {
-                    name: "Build Prerequisites",
-                    command: with_common_script!("commands/system-setup/1-compile-setup.sh"),
-                },
-                ListNode {
-                    name: "Gaming Dependencies",
-                    command: with_common_script!("commands/system-setup/2-gaming-setup.sh"),
-                },
-                ListNode {
-                    name: "Global Theme",
-                    command: with_common_script!("commands/system-setup/3-global-theme.sh"),
-                },
-                ListNode {
-                    name: "Recursion?",
-                    command: "cargo run"
-                }
-            },
-            ListNode {
-                name: "Titus Dotfiles",
-                command: ""
-            } => {
-                ListNode {
-                    name: "Alacritty Setup",
-                    command: with_common_script!("commands/dotfiles/alacritty-setup.sh"),
-                },
-                ListNode {
-                    name: "Kitty Setup",
-                    command: with_common_script!("commands/dotfiles/kitty-setup.sh"),
-                },
-                ListNode {
-                    name: "Rofi Setup",
-                    command: with_common_script!("commands/dotfiles/rofi-setup.sh"),
-                },
+                name: "Recursion?",
+                command: "cargo run",
+                script: None
             }
-        });
+        },
+        ListNode {
+            name: "Titus Dotfiles",
+            command: "",
+            script: None
+        } => {
+            script_node!("Alacritty Setup", "dotfiles/alacritty-setup.sh"),
+            script_node!("Kitty Setup", "dotfiles/kitty-setup.sh"),
+            script_node!("Rofi Setup", "dotfiles/rofi-setup.sh"),
+        }
+    })
+}
+
+pub fn running_as_root() -> bool {
+    // SAFETY: geteuid can't fail and has no preconditions
+    unsafe { libc::geteuid() == 0 }
+}
+
+/// `text` in single quotes, as a single argument for the shell
+pub fn shell_quote(text: &str) -> String {
+    format!("'{}'", text.replace('\'', r"'\''"))
+}
+
+/// Runs `command` with sudo if it needs to be. The whole script is passed to `sh` as a single
+/// argument
+fn with_privilege(
+    command: Cow<'static, str>,
+    privilege: PrivilegeMode,
+    is_root: bool,
+) -> Cow<'static, str> {
+    match privilege {
+        PrivilegeMode::AutoSudo if !is_root => {
+            Cow::Owned(format!("sudo sh -c {}", shell_quote(&command)))
+        }
+        _ => command,
+    }
+}
+
+/// The command `name` refers to, see `ListNode::is_called`
+pub fn lookup_command(name: &str) -> Option<ListNode> {
+    command_tree()
+        .root()
+        .descendants()
+        .map(|node| *node.value())
+        .filter(|node| !node.command.is_empty())
+        .find(|node| node.is_called(name))
+}
+
+/// Removes the commands the whitelist leaves out and the ones the blacklist has, and then the
+/// directories that have nothing left in them
+fn filter_tree(tree: &mut Tree<ListNode>, config: &TuiConfig) {
+    let hidden = |node: &ListNode| {
+        let listed = |names: &[String]| names.iter().any(|name| node.is_called(name));
+        config
+            .whitelist
+            .as_deref()
+            .is_some_and(|names| !listed(names))
+            || config.blacklist.as_deref().is_some_and(listed)
+    };
+    let root_id = tree.root().id();
+    let (commands, directories): (Vec<_>, Vec<_>) = tree
+        .root()
+        .descendants()
+        .filter(|node| node.id() != root_id)
+        .partition(|node| !node.value().command.is_empty());
+    let hidden_commands: Vec<NodeId> = commands
+        .into_iter()
+        .filter(|node| hidden(node.value()))
+        .map(|node| node.id())
+        .collect();
+    let directories: Vec<NodeId> = directories.into_iter().map(|node| node.id()).collect();
+
+    for id in hidden_commands {
+        tree.get_mut(id).unwrap().detach();
+    }
+    // Nested directories come after the ones they're in, so they're emptied first
+    for id in directories.into_iter().rev() {
+        let mut directory = tree.get_mut(id).unwrap();
+        if !directory.has_children() {
+            directory.detach();
+        }
+    }
+}
+
+impl CustomList {
+    pub fn new(config: &TuiConfig, history: History, bookmarks: BookmarkStore) -> Self {
+        let mut tree = command_tree();
+        filter_tree(&mut tree, config);
         // We don't get a reference, but rather an id, because references are siginficantly more
         // paintfull to manage
         let root_id = tree.root().id();
+        // The directories at the top of the tree are the categories, and so are the tags of the
+        // scripts
+        let categories = [Category::Recent, Category::Favorites, Category::All]
+            .into_iter()
+            .chain(
+                tree.root()
+                    .children()
+                    .filter(|node| node.has_children())
+                    .map(|node| Category::Directory(node.id())),
+            )
+            .chain(
+                metadata::all_tags()
+                    .into_iter()
+                    // The scripts of a tag could all be filtered out by the config
+                    .filter(|tag| {
+                        tree.root().descendants().any(|node| {
+                            let meta = node.value().script.and_then(metadata::find);
+                            meta.is_some_and(|meta| meta.tags.contains(tag))
+                        })
+                    })
+                    .map(Category::Tag),
+            )
+            .collect();
         Self {
             inner_tree: tree,
             visit_stack: vec![root_id],
             list_state: ListState::default().with_selected(Some(0)),
             // By default the PreviewWindowState is set to None, so it is not being shown
             preview_window_state: None,
+            filter: FilterBar::new(),
+            selected_commands: HashSet::new(),
+            list_area: Rect::default(),
+            categories,
+            // Start with everything
+            category_state: ListState::default().with_selected(Some(2)),
+            category_area: Rect::default(),
+            focus: Pane::Commands,
+            // Neither pane should disappear
+            category_width: config.category_width.clamp(10, 90),
+            read_only: config.read_only,
+            script_preview_shown: config.script_preview,
+            script_preview_height: config.preview_height,
+            history,
+            bookmarks,
+            bookmark_error: None,
         }
     }
 
     /// Draw our custom widget to the frame
-    pub fn draw(&mut self, frame: &mut Frame, area: Rect) {
-        // Get the last element in the `visit_stack` vec
-        let theme = get_theme();
-        let curr = self
-            .inner_tree
-            .get(*self.visit_stack.last().unwrap())
-            .unwrap();
+    /// If the config left out every command
+    pub fn is_empty(&self) -> bool {
+        !self.inner_tree.root().has_children()
+    }
+
+    pub fn draw(&mut self, frame: &mut Frame, area: Rect, theme: &Theme) {
         let mut items = vec![];
 
+        // Make room for the search bar at the bottom
+        let (area, filter_area) = if self.filter.is_shown() {
+            let [list_area, filter_area] =
+                Layout::vertical([Constraint::Min(1), Constraint::Length(3)]).areas(area);
+            (list_area, Some(filter_area))
+        } else {
+            (area, None)
+        };
+        let [category_area, commands_area] = Layout::horizontal([
+            Constraint::Percentage(self.category_width),
+            Constraint::Percentage(100 - self.category_width),
+        ])
+        .areas(area);
+        self.draw_categories(frame, category_area, theme);
+
+        // The script preview goes under the commands, plus 2 for its borders
+        let selected = self
+            .get_selected_node()
+            .map(|node| *self.inner_tree.get(node).unwrap().value());
+        let commands_area = match selected {
+            Some(node) if self.script_preview_shown && !node.command.is_empty() => {
+                let [commands_area, preview_area] = Layout::vertical([
+                    Constraint::Min(3),
+                    Constraint::Length(self.script_preview_height + 2),
+                ])
+                .areas(commands_area);
+                let meta = node.script.and_then(metadata::find);
+                script_preview::draw(frame, preview_area, theme, node.command, meta);
+                commands_area
+            }
+            _ => commands_area,
+        };
+
         // If we are not at the root of our filesystem tree, we need to add `..` path, to be able
         // to go up the tree
         // icons:   
-        if !self.at_root() {
+        if self.has_up_item() {
             items.push(Line::from(format!("{}  ..", theme.dir_icon)).style(theme.dir_color));
         }
 
-        // Iterate through all the children
-        for node in curr.children() {
+        // Iterate through all the visible nodes
+        for node in self.visible_nodes() {
+            let node = self.inner_tree.get(node).unwrap();
             // The difference between a "directory" and a "command" is simple: if it has children,
             // it's a directory and will be handled as such
             if node.has_children() {
@@ -156,9 +441,28 @@ impl CustomList {
                         .style(theme.dir_color),
                 );
             } else {
+                let picked = if self.selected_commands.contains(&node.id()) {
+                    "[x] "
+                } else {
+                    ""
+                };
+                let bookmarked = if self.bookmarks.contains(node.value().name) {
+                    "★ "
+                } else {
+                    ""
+                };
+                let privilege = match node.value().privilege() {
+                    PrivilegeMode::AsUser => "",
+                    PrivilegeMode::AutoSudo => theme.sudo_icon,
+                    PrivilegeMode::RequireSudo => theme.root_icon,
+                };
                 items.push(
-                    Line::from(format!("{}  {}", theme.cmd_icon, node.value().name))
-                        .style(theme.cmd_color),
+                    Line::from(format!(
+                        "{}  {picked}{bookmarked}{privilege}{}",
+                        theme.cmd_icon,
+                        node.value().name
+                    ))
+                    .style(theme.cmd_color),
                 );
             }
         }
@@ -166,15 +470,19 @@ impl CustomList {
         // create the normal list widget containing only item in our "working directory" / tree
         // node
         let list = List::new(items)
-            .highlight_style(Style::default().reversed())
-            .block(Block::default().borders(Borders::ALL).title(format!(
+            .highlight_style(Style::default().bg(theme.selection))
+            .block(self.pane_block(Pane::Commands, theme).title(format!(
                 "Linux Toolbox - {}",
                 chrono::Local::now().format("%Y-%m-%d")
             )))
             .scroll_padding(1);
 
         // Render it
-        frame.render_stateful_widget(list, area, &mut self.list_state);
+        self.list_area = commands_area;
+        frame.render_stateful_widget(list, commands_area, &mut self.list_state);
+        if let Some(filter_area) = filter_area {
+            self.filter.draw(frame, filter_area, theme);
+        }
 
         // Draw the preview window if it's active
         if let Some(pw_state) = &self.preview_window_state {
@@ -192,26 +500,112 @@ impl CustomList {
 
             // Create list widget
             let list = List::new(lines)
-                .block(
-                    Block::default()
-                        .borders(Borders::ALL)
-                        .title("Action preview"),
-                )
-                .highlight_style(Style::default().reversed());
+                .block(theme.block().title("Action preview"))
+                .highlight_style(Style::default().bg(theme.selection));
 
             // Finally render the preview window
             frame.render_widget(list, floating_area);
         }
     }
 
+    fn draw_categories(&mut self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        let items: Vec<Line> = self
+            .categories
+            .iter()
+            .map(|&category| Line::from(self.category_name(category)))
+            .collect();
+        let list = List::new(items)
+            .highlight_style(Style::default().bg(theme.selection))
+            .block(self.pane_block(Pane::Categories, theme).title("Categories"));
+        self.category_area = area;
+        frame.render_stateful_widget(list, area, &mut self.category_state);
+    }
+
+    /// The pane with the focus gets its border in the title color, to tell them apart
+    fn pane_block(&self, pane: Pane, theme: &Theme) -> Block<'static> {
+        let block = theme.block();
+        if self.focus == pane {
+            block.border_style(Style::default().fg(theme.title))
+        } else {
+            block
+        }
+    }
+
+    fn category_name(&self, category: Category) -> &'static str {
+        match category {
+            Category::Recent => "Recent",
+            Category::Favorites => "Favorites",
+            Category::All => "All",
+            Category::Directory(node) => self.inner_tree.get(node).unwrap().value().name,
+            Category::Tag(tag) => tag,
+        }
+    }
+
+    fn selected_category(&self) -> Category {
+        self.categories[self.category_state.selected().unwrap()]
+    }
+
+    /// Show the commands of the category at `idx` on the right
+    fn select_category(&mut self, idx: usize) {
+        self.category_state.select(Some(idx));
+        let root = self.inner_tree.root().id();
+        self.visit_stack = match self.selected_category() {
+            Category::Recent | Category::Favorites | Category::All | Category::Tag(_) => vec![root],
+            Category::Directory(node) => vec![root, node],
+        };
+        self.list_state.select(Some(0));
+    }
+
+    /// How deep the top of the selected category is in the `visit_stack`
+    fn category_depth(&self) -> usize {
+        match self.selected_category() {
+            Category::Recent | Category::Favorites | Category::All | Category::Tag(_) => 1,
+            Category::Directory(_) => 2,
+        }
+    }
+
+    /// Handle the keys for the categories pane, returns whether the key was for it
+    fn handle_category_key(&mut self, code: KeyCode, keys: &KeyBindings) -> bool {
+        let selected = self.category_state.selected().unwrap();
+        if keys.scroll_down.contains(&code) {
+            self.select_category((selected + 1).min(self.categories.len() - 1));
+        } else if keys.scroll_up.contains(&code) {
+            self.select_category(selected.saturating_sub(1));
+        } else if keys.open.contains(&code) {
+            self.focus = Pane::Commands;
+        } else {
+            return false;
+        }
+        true
+    }
+
     /// Handle key events, we are only interested in `Press` and `Repeat` events
-    pub fn handle_key(&mut self, event: KeyEvent) -> Option<&'static str> {
+    /// Returns the commands to run, when the user asks to run something
+    pub fn handle_key(&mut self, event: KeyEvent, keys: &KeyBindings) -> Option<Vec<ListNode>> {
         if event.kind == KeyEventKind::Release {
             return None;
         }
+        // While the search bar is focused, it gets all the keys
+        if self.filter.is_focused() {
+            if self.filter.handle_key(event) {
+                // The old selection might not even exist in the new results
+                self.list_state.select(Some(0));
+            }
+            return None;
+        }
+        if keys.switch_pane.contains(&event.code) {
+            self.focus = match self.focus {
+                Pane::Categories => Pane::Commands,
+                Pane::Commands => Pane::Categories,
+            };
+            return None;
+        }
+        if self.focus == Pane::Categories && self.handle_category_key(event.code, keys) {
+            return None;
+        }
         match event.code {
             // Damm you Up arrow, use vim lol
-            KeyCode::Char('j') | KeyCode::Down => {
+            code if keys.scroll_down.contains(&code) => {
                 // If the preview window is active, scroll down and consume the scroll action,
                 // so the scroll does not happen in the main window as well
                 if self.preview_window_state.is_some() {
@@ -222,7 +616,7 @@ impl CustomList {
                 self.try_scroll_down();
                 None
             }
-            KeyCode::Char('k') | KeyCode::Up => {
+            code if keys.scroll_up.contains(&code) => {
                 // If the preview window is active, scroll up and consume the scroll action,
                 // so the scroll does not happen in the main window as well
                 if self.preview_window_state.is_some() {
@@ -233,15 +627,124 @@ impl CustomList {
                 self.try_scroll_up();
                 None
             }
+            // Space picks or unpicks the command for running multiple at once
+            code if keys.pick.contains(&code) => {
+                self.toggle_selection();
+                None
+            }
             // The 'p' key toggles the preview on and off
-            KeyCode::Char('p') => {
+            code if keys.preview.contains(&code) => {
                 self.toggle_preview_window();
                 None
             }
-            KeyCode::Enter => self.handle_enter(),
+            code if keys.bookmark.contains(&code) => {
+                self.toggle_bookmark();
+                None
+            }
+            code if keys.script_preview.contains(&code) => {
+                self.script_preview_shown = !self.script_preview_shown;
+                None
+            }
+            code if keys.search.contains(&code) => {
+                self.filter.focus();
+                None
+            }
+            code if keys.clear_search.contains(&code) && self.filter.is_filtering() => {
+                self.filter.clear();
+                self.list_state.select(Some(0));
+                None
+            }
+            code if keys.open.contains(&code) => self.handle_enter(),
             _ => None,
         }
     }
+    /// The keys the list reacts to, for the help
+    pub fn get_shortcut_list(&self, keys: &KeyBindings) -> (&str, Box<[Shortcut]>) {
+        (
+            "Command list",
+            Box::new([
+                Shortcut::new(describe(&keys.scroll_down), "Move down"),
+                Shortcut::new(describe(&keys.scroll_up), "Move up"),
+                Shortcut::new(
+                    describe(&keys.switch_pane),
+                    "Switch between the categories and the commands",
+                ),
+                Shortcut::new(describe(&keys.open), "Open a directory, or run a command")
+                    .disabled_if(self.read_only),
+                Shortcut::new(
+                    describe(&keys.pick),
+                    "Pick a command, to run it with others",
+                )
+                .disabled_if(self.read_only),
+                Shortcut::new(describe(&keys.preview), "Preview the command"),
+                Shortcut::new(describe(&keys.bookmark), "Bookmark the command"),
+                Shortcut::new(
+                    describe(&keys.script_preview),
+                    "Toggle the start of the script under the list",
+                ),
+                Shortcut::new(describe(&keys.search), "Search"),
+                Shortcut::new(describe(&keys.clear_search), "Clear the search"),
+                Shortcut::new(describe(&keys.dry_run), "Toggle the dry run"),
+                Shortcut::new(
+                    describe_ctrl(&keys.log_history),
+                    "Show the scripts that were run before",
+                ),
+                Shortcut::new(describe_ctrl(&keys.toggle_theme), "Toggle the dark theme"),
+                Shortcut::new(describe(&keys.help), "Show this help"),
+                Shortcut::new(
+                    describe(&keys.shortcuts),
+                    "Toggle the shortcuts at the bottom",
+                ),
+                Shortcut::new(describe(&keys.quit), "Quit"),
+            ]),
+        )
+    }
+
+    /// Handle mouse events: clicking an item selects it, clicking the selected item again acts
+    /// like <Enter>, and the wheel scrolls
+    pub fn handle_mouse(&mut self, event: MouseEvent) -> Option<Vec<ListNode>> {
+        match event.kind {
+            MouseEventKind::Down(MouseButton::Left) => {
+                let position = Position::new(event.column, event.row);
+                let categories = self.category_area.inner(Margin::new(1, 1));
+                if categories.contains(position) {
+                    let clicked =
+                        self.category_state.offset() + (event.row - categories.y) as usize;
+                    if clicked < self.categories.len() {
+                        self.focus = Pane::Categories;
+                        self.select_category(clicked);
+                    }
+                    return None;
+                }
+
+                let inner = self.list_area.inner(Margin::new(1, 1));
+                if !inner.contains(Position::new(event.column, event.row)) {
+                    return None;
+                }
+                let clicked = self.list_state.offset() + (event.row - inner.y) as usize;
+                let count = self.visible_nodes().len() + usize::from(self.has_up_item());
+                if clicked >= count {
+                    return None;
+                }
+                self.focus = Pane::Commands;
+                if self.list_state.selected() == Some(clicked) {
+                    return self.handle_enter();
+                }
+                self.list_state.select(Some(clicked));
+                None
+            }
+            MouseEventKind::ScrollDown if self.preview_window_state.is_none() => {
+                self.try_scroll_down();
+                None
+            }
+            MouseEventKind::ScrollUp if self.preview_window_state.is_none() => {
+                self.try_scroll_up();
+                None
+            }
+            _ => None,
+        }
+    }
+
     fn toggle_preview_window(&mut self) {
         // If the preview window is active, disable it
         if self.preview_window_state.is_some() {
@@ -268,27 +771,57 @@ impl CustomList {
             }
         }
     }
+    fn toggle_selection(&mut self) {
+        if let Some(node) = self.get_selected_node() {
+            // Directories can't be run, so there is nothing to pick
+            if self.inner_tree.get(node).unwrap().has_children() {
+                return;
+            }
+            if !self.selected_commands.remove(&node) {
+                self.selected_commands.insert(node);
+            }
+        }
+    }
+
+    fn toggle_bookmark(&mut self) {
+        let Some(node) = self.get_selected_node() else {
+            return;
+        };
+        let node = self.inner_tree.get(node).unwrap();
+        // Only commands can be bookmarked, not directories
+        if node.has_children() {
+            return;
+        }
+        self.bookmarks.toggle(node.value().name);
+        self.bookmark_error = self.bookmarks.save().err();
+    }
+
+    /// Why the bookmarks couldn't be saved the last time, if they couldn't
+    pub fn take_bookmark_error(&mut self) -> Option<ConfigError> {
+        self.bookmark_error.take()
+    }
+
+    /// Forget the commands picked with <Space>, once they are on their way to be run
+    pub fn clear_selection(&mut self) {
+        self.selected_commands.clear();
+    }
+
     fn try_scroll_up(&mut self) {
         self.list_state
             .select(Some(self.list_state.selected().unwrap().saturating_sub(1)));
     }
     fn try_scroll_down(&mut self) {
-        let curr = self
-            .inner_tree
-            .get(*self.visit_stack.last().unwrap())
-            .unwrap();
-
-        let count = curr.children().count();
+        let count = self.visible_nodes().len();
 
         let curr_selection = self.list_state.selected().unwrap();
-        if self.at_root() {
-            self.list_state
-                .select(Some((curr_selection + 1).min(count - 1)));
-        } else {
+        if self.has_up_item() {
             // When we are not at the root, we have to account for 1 more "virtual" node, `..`. So
             // the count is 1 bigger (select is 0 based, because it's an index)
             self.list_state
                 .select(Some((curr_selection + 1).min(count)));
+        } else {
+            self.list_state
+                .select(Some((curr_selection + 1).min(count.saturating_sub(1))));
         }
     }
 
@@ -316,23 +849,25 @@ impl CustomList {
     /// This could probably be integrated into the 'handle_enter()' method as to avoid code
     /// duplication, but I don't want to make too major changes to the codebase.
     fn get_selected_command(&self) -> Option<&'static str> {
-        let curr = self
-            .inner_tree
-            .get(*self.visit_stack.last().unwrap())
-            .unwrap();
+        self.get_selected_node()
+            .map(|node| self.inner_tree.get(node).unwrap().value().command)
+    }
+
+    /// The node under the cursor, or None if it's the `..` item
+    fn get_selected_node(&self) -> Option<NodeId> {
         let selected = self.list_state.selected().unwrap();
 
         // If we are not at the root and the first item is selected, it's the `..` item
-        if !self.at_root() && selected == 0 {
+        if self.has_up_item() && selected == 0 {
             return None;
         }
 
-        for (mut idx, node) in curr.children().enumerate() {
-            if !self.at_root() {
+        for (mut idx, node) in self.visible_nodes().into_iter().enumerate() {
+            if self.has_up_item() {
                 idx += 1;
             }
             if idx == selected {
-                return Some(node.value().command);
+                return Some(node);
             }
         }
         None
@@ -342,46 +877,212 @@ impl CustomList {
     /// - Run a command, if it is the currently selected item,
     /// - Go up a directory
     /// - Go down into a directory
-    /// Returns `Some(command)` when command is selected, othervise we returns `None`
-    fn handle_enter(&mut self) -> Option<&'static str> {
-        // Get the current node (current directory)
-        let curr = self
-            .inner_tree
-            .get(*self.visit_stack.last().unwrap())
-            .unwrap();
+    ///
+    /// Returns the commands picked with <Space> if there are any, or `Some(vec![command])` when
+    /// a command is selected, othervise we returns `None`
+    fn handle_enter(&mut self) -> Option<Vec<ListNode>> {
         let selected = self.list_state.selected().unwrap();
 
         // if we are not at the root, and the first element is selected,
         // we can be sure it's '..', so we go up the directory
-        if !self.at_root() && selected == 0 {
+        if self.has_up_item() && selected == 0 {
             self.visit_stack.pop();
             self.list_state.select(Some(0));
             return None;
         }
 
-        for (mut idx, node) in curr.children().enumerate() {
+        for (mut idx, node) in self.visible_nodes().into_iter().enumerate() {
             // at this point, we know that we are not on the .. item, and our indexes of the items never had ..
             // item. so to balance it out, in case the selection index contains .., se add 1 to our node index
-            if !self.at_root() {
+            if self.has_up_item() {
                 idx += 1;
             }
+            let node = self.inner_tree.get(node).unwrap();
             if idx == selected {
                 if node.has_children() {
                     self.visit_stack.push(node.id());
                     self.list_state.select(Some(0));
                     return None;
+                } else if self.selected_commands.is_empty() {
+                    return Some(vec![*node.value()]);
                 } else {
-                    return Some(node.value().command);
+                    // Keep the order of the tree, instead of the order they were picked in
+                    let picked = self
+                        .inner_tree
+                        .root()
+                        .descendants()
+                        .filter(|node| self.selected_commands.contains(&node.id()))
+                        .map(|node| *node.value())
+                        .collect();
+                    return Some(picked);
                 }
             }
         }
         None
     }
 
+    /// The nodes shown in the list, not counting the `..` item. Normally these are the children of
+    /// the current directory, but while filtering, it's every matching command in the category
+    fn visible_nodes(&self) -> Vec<NodeId> {
+        // These categories are lists of names, instead of a part of the tree
+        let names: Option<Vec<&str>> = match self.selected_category() {
+            Category::Recent => Some(self.history.iter().collect()),
+            Category::Favorites => Some(self.bookmarks.iter().collect()),
+            Category::Tag(tag) => Some(
+                self.inner_tree
+                    .root()
+                    .descendants()
+                    .filter(|node| {
+                        let meta = node.value().script.and_then(metadata::find);
+                        meta.is_some_and(|meta| meta.tags.contains(&tag))
+                    })
+                    .map(|node| node.value().name)
+                    .collect(),
+            ),
+            Category::All | Category::Directory(_) => None,
+        };
+        if let Some(names) = names {
+            return names
+                .into_iter()
+                .filter(|name| self.filter.matches(name))
+                .filter_map(|name| self.find_command(name))
+                .collect();
+        }
+        if self.filter.is_filtering() {
+            let category_root = self.visit_stack[self.category_depth() - 1];
+            self.inner_tree
+                .get(category_root)
+                .unwrap()
+                .descendants()
+                .filter(|node| !node.has_children() && self.filter.matches(node.value().name))
+                .map(|node| node.id())
+                .collect()
+        } else {
+            self.inner_tree
+                .get(*self.visit_stack.last().unwrap())
+                .unwrap()
+                .children()
+                .map(|node| node.id())
+                .collect()
+        }
+    }
+
+    /// The command with this name, commands that were removed since they were run aren't found
+    fn find_command(&self, name: &str) -> Option<NodeId> {
+        self.inner_tree
+            .root()
+            .descendants()
+            .find(|node| !node.has_children() && node.value().name == name)
+            .map(|node| node.id())
+    }
+
+    /// Remember that the commands were run, for the recent category
+    pub fn record_run(&mut self, nodes: &[ListNode]) -> Result<(), ConfigError> {
+        for node in nodes {
+            self.history.push(node.name);
+        }
+        self.history.save()
+    }
+
+    /// Whether the list starts with the `..` item. Search results are not in any directory, so
+    /// there is nothing to go up from
+    fn has_up_item(&self) -> bool {
+        !self.at_root() && !self.filter.is_filtering()
+    }
+
+    /// Whether key presses are currently being typed into the search bar
+    pub fn is_filter_focused(&self) -> bool {
+        self.filter.is_focused()
+    }
+
     /// Checks weather the current tree node is the root node (can we go up the tree or no)
-    /// Returns `true` if we can't go up the tree (we are at the tree root)
-    /// else returns `false`
+    /// Returns `true` if we can't go up the tree (we are at the tree root, or the top of the
+    /// category) else returns `false`
     fn at_root(&self) -> bool {
-        self.visit_stack.len() == 1
+        self.visit_stack.len() == self.category_depth()
+    }
+}
+
+#[test]
+fn test_full_command() {
+    let script = script_node!("Full System Update", "system-update.sh");
+    assert!(!script.command.contains(COMMON_SCRIPT));
+    assert_eq!(
+        script.full_command(),
+        format!("{COMMON_SCRIPT}{}", script.command)
+    );
+
+    let command = ListNode {
+        name: "Recursion?",
+        command: "cargo run",
+        script: None,
+    };
+    assert_eq!(command.full_command(), "cargo run");
+}
+
+#[test]
+fn test_with_privilege() {
+    let command = || Cow::Borrowed("echo 'hi'");
+    assert_eq!(
+        with_privilege(command(), PrivilegeMode::AutoSudo, false),
+        r"sudo sh -c 'echo '\''hi'\'''"
+    );
+    assert_eq!(
+        with_privilege(command(), PrivilegeMode::AutoSudo, true),
+        "echo 'hi'"
+    );
+    assert_eq!(
+        with_privilege(command(), PrivilegeMode::RequireSudo, false),
+        "echo 'hi'"
+    );
+}
+
+#[test]
+fn test_lookup_command() {
+    for name in [
+        "Kitty Setup",
+        "kitty setup",
+        "dotfiles/kitty-setup.sh",
+        "dotfiles/kitty-setup",
+        "kitty-setup",
+    ] {
+        assert_eq!(
+            lookup_command(name).map(|node| node.name),
+            Some("Kitty Setup")
+        );
     }
+    // Directories can't be run
+    assert!(lookup_command("Titus Dotfiles").is_none());
+    assert!(lookup_command("docker-install").is_none());
+}
+
+#[test]
+fn test_filter_tree() {
+    let names = |tree: &Tree<ListNode>| -> Vec<&str> {
+        tree.root()
+            .descendants()
+            .skip(1)
+            .map(|node| node.value().name)
+            .collect()
+    };
+
+    let mut tree = command_tree();
+    let config = TuiConfig {
+        whitelist: Some(vec!["system-update".to_string(), "kitty setup".to_string()]),
+        ..TuiConfig::default()
+    };
+    filter_tree(&mut tree, &config);
+    assert_eq!(
+        names(&tree),
+        ["Full System Update", "Titus Dotfiles", "Kitty Setup"]
+    );
+
+    let mut tree = command_tree();
+    let config = TuiConfig {
+        blacklist: Some(vec!["Recursion?".to_string()]),
+        ..TuiConfig::default()
+    };
+    filter_tree(&mut tree, &config);
+    assert!(!names(&tree).contains(&"Recursion?"));
+    assert!(names(&tree).contains(&"System Setup"));
 }
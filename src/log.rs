@@ -0,0 +1,164 @@
+use std::{
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::PathBuf,
+    time::Duration,
+};
+
+use chrono::{DateTime, FixedOffset, Local, SecondsFormat};
+
+use crate::config::{data_dir, ConfigError, TuiConfig};
+
+/// How many runs the log keeps, the oldest ones are dropped once there are more
+const MAX_ENTRIES: usize = 10_000;
+
+/// One run of one or more scripts
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogEntry {
+    /// When the run started
+    pub timestamp: DateTime<FixedOffset>,
+    /// The names of the scripts that were run together
+    pub script: String,
+    pub exit_code: u32,
+    pub duration: Duration,
+}
+
+impl LogEntry {
+    /// A run that started `duration` ago and just ended
+    pub fn finished_now(script: String, exit_code: u32, duration: Duration) -> Self {
+        let timestamp = Local::now() - duration;
+        Self {
+            timestamp: timestamp.fixed_offset(),
+            script,
+            exit_code,
+            duration,
+        }
+    }
+
+    /// A line of `run.log`: the ISO-8601 timestamp, the script, the exit code, and the duration
+    /// in milliseconds, separated by tabs
+    fn to_line(&self) -> String {
+        format!(
+            "{}\t{}\t{}\t{}",
+            self.timestamp.to_rfc3339_opts(SecondsFormat::Secs, false),
+            self.script.replace(['\t', '\n'], " "),
+            self.exit_code,
+            self.duration.as_millis()
+        )
+    }
+
+    fn from_line(line: &str) -> Option<Self> {
+        let mut fields = line.split('\t');
+        let timestamp = DateTime::parse_from_rfc3339(fields.next()?).ok()?;
+        let script = fields.next()?.to_string();
+        let exit_code = fields.next()?.parse().ok()?;
+        let duration = Duration::from_millis(fields.next()?.parse().ok()?);
+        if fields.next().is_some() {
+            return None;
+        }
+        Some(Self {
+            timestamp,
+            script,
+            exit_code,
+            duration,
+        })
+    }
+}
+
+/// Every script that was run, with its exit code, in `run.log` in the data directory, or where
+/// the config says
+#[derive(Debug)]
+pub struct ExecutionLog {
+    path: Option<PathBuf>,
+}
+
+impl ExecutionLog {
+    pub fn new(config: &TuiConfig) -> Self {
+        Self {
+            path: config
+                .log_path
+                .clone()
+                .or_else(|| data_dir().map(|dir| dir.join("run.log"))),
+        }
+    }
+
+    /// Add a run to the end of the log, dropping the oldest runs if it gets too long
+    pub fn append(&self, entry: LogEntry) -> Result<(), ConfigError> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let write = |path: &PathBuf| {
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir)?;
+            }
+            let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+            writeln!(file, "{}", entry.to_line())
+        };
+        write(path).map_err(|err| ConfigError::Write(path.clone(), err))?;
+        self.rotate()
+    }
+
+    /// Only keep the newest `MAX_ENTRIES` lines
+    fn rotate(&self) -> Result<(), ConfigError> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let contents =
+            fs::read_to_string(path).map_err(|err| ConfigError::Read(path.clone(), err))?;
+        let line_count = contents.lines().count();
+        if line_count <= MAX_ENTRIES {
+            return Ok(());
+        }
+        let kept: Vec<&str> = contents.lines().skip(line_count - MAX_ENTRIES).collect();
+        fs::write(path, kept.join("\n") + "\n").map_err(|err| ConfigError::Write(path.clone(), err))
+    }
+
+    /// All the runs in the log, the oldest one first. Lines that can't be read are left out
+    pub fn load(&self) -> Result<Vec<LogEntry>, ConfigError> {
+        let Some(path) = &self.path else {
+            return Ok(Vec::new());
+        };
+        match fs::read_to_string(path) {
+            Ok(contents) => Ok(contents.lines().filter_map(LogEntry::from_line).collect()),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+            Err(err) => Err(ConfigError::Read(path.clone(), err)),
+        }
+    }
+}
+
+#[test]
+fn test_execution_log() {
+    use crate::test_dir::TestDir;
+
+    let dir = TestDir::new("log");
+    let log = ExecutionLog {
+        path: Some(dir.join("run.log")),
+    };
+    assert_eq!(log.load().unwrap(), []);
+
+    let entry = LogEntry {
+        timestamp: DateTime::parse_from_rfc3339("2024-08-01T12:30:00+02:00").unwrap(),
+        script: "Full System Update".to_string(),
+        exit_code: 1,
+        duration: Duration::from_millis(1500),
+    };
+    assert_eq!(
+        entry.to_line(),
+        "2024-08-01T12:30:00+02:00\tFull System Update\t1\t1500"
+    );
+    log.append(entry.clone()).unwrap();
+    assert_eq!(log.load().unwrap(), std::slice::from_ref(&entry));
+
+    // A full log
+    let line = entry.to_line() + "\n";
+    fs::write(dir.join("run.log"), line.repeat(MAX_ENTRIES)).unwrap();
+    let last = LogEntry {
+        exit_code: 0,
+        ..entry.clone()
+    };
+    log.append(last.clone()).unwrap();
+
+    let entries = log.load().unwrap();
+    assert_eq!(entries.len(), MAX_ENTRIES);
+    assert_eq!(entries.last(), Some(&last));
+}
@@ -0,0 +1,135 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::{Alignment, Constraint, Rect},
+    style::{Style, Stylize},
+    text::Line,
+    widgets::{Clear, Row, Table},
+    Frame,
+};
+
+use crate::{
+    config::{describe, KeyBindings},
+    float::{FloatContent, FloatEvent},
+    hint::Shortcut,
+    log::LogEntry,
+    theme::Theme,
+};
+
+/// The scripts that were run before, from the execution log, the most recent one first
+pub struct LogHistoryFloat {
+    entries: Vec<LogEntry>,
+    /// How many entries are scrolled past
+    scroll: usize,
+}
+
+impl LogHistoryFloat {
+    /// Takes the entries in the order they are in the log
+    pub fn new(mut entries: Vec<LogEntry>) -> Self {
+        entries.reverse();
+        Self { entries, scroll: 0 }
+    }
+
+    fn scroll_down(&mut self) {
+        if self.scroll + 1 < self.entries.len() {
+            self.scroll += 1;
+        }
+    }
+
+    fn scroll_up(&mut self) {
+        self.scroll = self.scroll.saturating_sub(1);
+    }
+}
+
+impl FloatContent for LogHistoryFloat {
+    fn draw(&mut self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        let block = theme
+            .block()
+            .title(" Run log ")
+            .title_alignment(Alignment::Center)
+            .title_style(Style::default().fg(theme.title).bold())
+            .title_bottom(Line::from(" [j/k] to scroll, [q] to close ").centered());
+
+        let header =
+            Row::new(["Started", "Script", "Exit code", "Took"]).style(Style::default().bold());
+        let rows = self.entries.iter().skip(self.scroll).map(|entry| {
+            let exit_color = if entry.exit_code == 0 {
+                theme.success_color
+            } else {
+                theme.fail_color
+            };
+            Row::new([
+                Line::from(entry.timestamp.format("%Y-%m-%d %H:%M:%S").to_string()),
+                Line::from(entry.script.as_str()),
+                Line::styled(entry.exit_code.to_string(), Style::default().fg(exit_color)),
+                Line::from(format!("{:.1}s", entry.duration.as_secs_f64())),
+            ])
+        });
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(19),
+                Constraint::Min(10),
+                Constraint::Length(9),
+                Constraint::Length(8),
+            ],
+        )
+        .header(header)
+        .block(block);
+
+        frame.render_widget(Clear, area);
+        if self.entries.is_empty() {
+            frame.render_widget(table.rows([Row::new(["Nothing was run yet"])]), area);
+        } else {
+            frame.render_widget(table, area);
+        }
+    }
+
+    fn handle_key_event(&mut self, key: &KeyEvent, keys: &KeyBindings) -> FloatEvent {
+        match key.code {
+            code if keys.scroll_down.contains(&code) => self.scroll_down(),
+            code if keys.scroll_up.contains(&code) => self.scroll_up(),
+            code if keys.close.contains(&code) => return FloatEvent::CloseFloat,
+            KeyCode::Esc => return FloatEvent::CloseFloat,
+            _ => {}
+        }
+        FloatEvent::None
+    }
+
+    fn is_finished(&self) -> bool {
+        false
+    }
+
+    fn get_shortcut_list(&self, keys: &KeyBindings) -> (&str, Box<[Shortcut]>) {
+        let close_keys: Vec<KeyCode> = [&keys.close[..], &[KeyCode::Esc]].concat();
+        (
+            "Run log",
+            Box::new([
+                Shortcut::new(describe(&keys.scroll_down), "Scroll down"),
+                Shortcut::new(describe(&keys.scroll_up), "Scroll up"),
+                Shortcut::new(describe(&close_keys), "Close the run log"),
+            ]),
+        )
+    }
+
+    fn preferred_size(&self) -> (u16, u16) {
+        // The borders and the header, too
+        let height = self.entries.len() + 3;
+        (90, height.clamp(6, usize::from(u16::MAX)) as u16)
+    }
+}
+
+#[test]
+fn test_log_history_newest_first() {
+    use std::time::Duration;
+
+    let entries: Vec<LogEntry> = (0..3)
+        .map(|idx| LogEntry::finished_now(format!("script {idx}"), idx, Duration::ZERO))
+        .collect();
+    let mut history = LogHistoryFloat::new(entries);
+    assert_eq!(history.entries[0].script, "script 2");
+
+    for _ in 0..5 {
+        history.handle_key_event(&KeyEvent::from(KeyCode::Char('j')), &KeyBindings::default());
+    }
+    assert_eq!(history.scroll, 2);
+}
@@ -0,0 +1,150 @@
+use crossterm::event::KeyEvent;
+use ratatui::{
+    layout::{Alignment, Rect},
+    style::{Style, Stylize},
+    text::Line,
+    widgets::{Clear, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::{
+    config::{describe, KeyBindings},
+    float::{FloatContent, FloatEvent},
+    hint::Shortcut,
+    theme::Theme,
+};
+
+/// Shows the output of a script as plain, scrollable lines, while it's still running
+#[derive(Default)]
+pub struct LogViewerFloat {
+    lines: Vec<String>,
+    /// How many lines are scrolled past
+    scroll: usize,
+    /// Set once the script exited, no more lines will be pushed after that
+    script_exited: bool,
+    /// How many lines fit in the float, the last time it was drawn
+    page_size: usize,
+}
+
+impl LogViewerFloat {
+    /// The log of a script that already exited
+    pub fn finished(lines: Vec<String>) -> Self {
+        Self {
+            lines,
+            script_exited: true,
+            ..Self::default()
+        }
+    }
+
+    pub fn push_line(&mut self, line: String) {
+        self.lines.push(line);
+    }
+
+    /// How many lines were pushed so far
+    pub fn line_count(&self) -> usize {
+        self.lines.len()
+    }
+
+    /// The script is done, so is the log
+    pub fn mark_exited(&mut self) {
+        self.script_exited = true;
+    }
+
+    fn scroll_down(&mut self) {
+        if self.scroll + 1 < self.lines.len() {
+            self.scroll += 1;
+        }
+    }
+
+    fn scroll_up(&mut self) {
+        self.scroll = self.scroll.saturating_sub(1);
+    }
+
+    /// Scroll far enough for the last line to be at the bottom
+    fn scroll_to_end(&mut self) {
+        self.scroll = self.lines.len().saturating_sub(self.page_size.max(1));
+    }
+}
+
+impl FloatContent for LogViewerFloat {
+    fn draw(&mut self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        let title = if self.script_exited {
+            " Output "
+        } else {
+            " Output (running) "
+        };
+        let block = theme
+            .block()
+            .title(title)
+            .title_alignment(Alignment::Center)
+            .title_style(Style::default().fg(theme.title).bold())
+            .title_bottom(Line::from(" [j/k] to scroll, [G] to the end, [q] to close ").centered());
+
+        let inner_area = block.inner(area);
+        self.page_size = inner_area.height as usize;
+        frame.render_widget(Clear, area);
+
+        let lines: Vec<Line> = self.lines[self.scroll.min(self.lines.len())..]
+            .iter()
+            .map(|line| Line::from(line.as_str()))
+            .collect();
+        let paragraph = Paragraph::new(lines).wrap(Wrap::default()).block(block);
+        frame.render_widget(paragraph, area);
+    }
+
+    fn handle_key_event(&mut self, key: &KeyEvent, keys: &KeyBindings) -> FloatEvent {
+        match key.code {
+            code if keys.scroll_down.contains(&code) => self.scroll_down(),
+            code if keys.scroll_up.contains(&code) => self.scroll_up(),
+            code if keys.scroll_to_end.contains(&code) => self.scroll_to_end(),
+            code if keys.close.contains(&code) => return FloatEvent::CloseFloat,
+            _ => {}
+        }
+        FloatEvent::None
+    }
+
+    fn is_finished(&self) -> bool {
+        self.script_exited
+    }
+
+    fn get_shortcut_list(&self, keys: &KeyBindings) -> (&str, Box<[Shortcut]>) {
+        (
+            "Output",
+            Box::new([
+                Shortcut::new(describe(&keys.scroll_down), "Scroll down"),
+                Shortcut::new(describe(&keys.scroll_up), "Scroll up"),
+                Shortcut::new(describe(&keys.scroll_to_end), "Jump to the end"),
+                Shortcut::new(describe(&keys.close), "Close the output"),
+            ]),
+        )
+    }
+
+    /// Logs can get long, so take all the space there is
+    fn preferred_size(&self) -> (u16, u16) {
+        (u16::MAX, u16::MAX)
+    }
+}
+
+#[test]
+fn test_log_viewer_keys() {
+    use crossterm::event::KeyCode;
+
+    let keys = KeyBindings::default();
+    let mut log = LogViewerFloat::default();
+    for idx in 0..10 {
+        log.push_line(format!("line {idx}"));
+    }
+    log.page_size = 4;
+
+    log.handle_key_event(&KeyEvent::from(KeyCode::Char('G')), &keys);
+    assert_eq!(log.scroll, 6);
+    log.handle_key_event(&KeyEvent::from(KeyCode::Char('k')), &keys);
+    assert_eq!(log.scroll, 5);
+
+    // The log can be closed while the script still runs
+    assert!(!log.is_finished());
+    assert_eq!(
+        log.handle_key_event(&KeyEvent::from(KeyCode::Char('q')), &keys),
+        FloatEvent::CloseFloat
+    );
+}
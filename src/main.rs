@@ -1,28 +1,78 @@
+mod app;
+mod bookmarks;
+mod chain;
+mod cli;
+mod config;
+mod confirmation;
+mod dry_run;
+mod filter;
 mod float;
+mod float_stack;
+mod help;
+mod hint;
+mod history;
 mod list;
+mod log;
+mod log_history;
+mod log_viewer;
+mod metadata;
+mod post_run;
+mod preflight;
 mod running_command;
+mod script_params;
+mod script_preview;
+mod spinner;
+mod status_bar;
+#[cfg(test)]
+mod test_dir;
 mod theme;
 
 use std::{
     io::{self, stdout},
+    sync::mpsc,
+    thread,
     time::Duration,
 };
 
+use app::TuiApp;
+use bookmarks::BookmarkStore;
+use chain::ScriptChain;
 use clap::Parser;
+use config::{KeyBindings, TuiConfig};
+use confirmation::{ConfirmPrompt, NonInteractiveOutput, ReadOnlyNotice};
 use crossterm::{
     cursor::RestorePosition,
-    event::{self, DisableMouseCapture, Event, KeyCode, KeyEventKind},
+    event::{
+        self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind, KeyModifiers,
+    },
     style::ResetColor,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
     ExecutableCommand,
 };
-use list::CustomList;
+use dry_run::DryRunFloat;
+use float::{FloatContent, FloatEvent};
+use float_stack::{Float, FloatStack};
+use help::HelpFloat;
+use hint::ShortcutOverlay;
+use history::History;
+use list::{CustomList, ListNode};
+use log::{ExecutionLog, LogEntry};
+use log_history::LogHistoryFloat;
+use log_viewer::LogViewerFloat;
+use post_run::PostRunFloat;
+use preflight::MissingDepsFloat;
 use ratatui::{
     backend::{Backend, CrosstermBackend},
+    layout::{Constraint, Layout},
+    widgets::Block,
     Terminal,
 };
-use running_command::RunningCommand;
-use theme::set_theme;
+use running_command::{ExecutionConfig, RunningCommand};
+use script_params::ScriptParamFloat;
+use spinner::Spinner;
+use status_bar::StatusBar;
+use theme::{get_theme, set_theme, ThemeChoice};
+use tui::systeminfo::System;
 
 /// This is a binary :), Chris, change this to update the documentation on -h
 #[derive(Debug, Parser)]
@@ -30,20 +80,152 @@ struct Args {
     /// Enable compatibility mode (disable icons and RGB colors)
     #[arg(short, long, default_value_t = false)]
     compat: bool,
+    /// Print the detected system information and exit
+    #[arg(long, default_value_t = false)]
+    sysinfo: bool,
+    /// Run the script with this name, or the path of the script, without the TUI. Exits with the
+    /// exit code of the script
+    #[arg(long, value_name = "NAME")]
+    script: Option<String>,
+    /// Ask before running the script given to --script. Without a terminal, the first line of
+    /// stdin is the answer. --yes runs it without asking
+    #[arg(long, default_value_t = false, requires = "script")]
+    confirm: bool,
+    /// Run the scripts named on stdin, one per line, without the TUI. Prints a summary of how
+    /// they went, as JSON
+    #[arg(long, default_value_t = false, conflicts_with = "script")]
+    non_interactive: bool,
+    /// Print the path of every script in src/commands and exit
+    #[arg(long, default_value_t = false)]
+    list_scripts: bool,
+    /// Print the detected system information as JSON and exit. With --list-scripts, print the
+    /// metadata of the scripts as JSON instead
+    #[cfg(feature = "serde")]
+    #[arg(long, default_value_t = false)]
+    json: bool,
+    /// Run the selected commands by themselves, if they aren't confirmed within this many seconds
+    #[arg(long, value_name = "SECONDS")]
+    confirm_timeout: Option<u8>,
+    /// Show what the confirmed commands would run, instead of running them
+    #[arg(long, default_value_t = false)]
+    dry_run: bool,
+    /// Answer yes to every confirmation prompt, in the TUI and for --confirm
+    #[arg(short, long, default_value_t = false)]
+    yes: bool,
+    /// Only look at the commands, without running any. Dry runs still work
+    #[arg(long, default_value_t = false)]
+    read_only: bool,
+    /// Detect the system before the TUI starts, instead of in the background once it's up
+    #[arg(long, default_value_t = false)]
+    detect_first: bool,
 }
 
 fn main() -> std::io::Result<()> {
     let args = Args::parse();
+    let mut config = TuiConfig::load().unwrap_or_else(|err| {
+        eprintln!("Failed to load the config: {err}");
+        std::process::exit(1);
+    });
+    config.read_only |= args.read_only;
+    let mut execution = ExecutionConfig {
+        timeout: config.timeout.map(Duration::from_secs),
+        ..ExecutionConfig::default()
+    };
+    let execution_log = ExecutionLog::new(&config);
+    if config.read_only && (args.script.is_some() || args.non_interactive) {
+        eprintln!("Nothing can be run in read-only mode");
+        std::process::exit(1);
+    }
+    if args.script.is_some() || args.non_interactive {
+        // The TUI detects the system in the background, there's nothing to show while waiting here
+        execution.env = System::info()
+            .map(|system| running_command::script_env(&system))
+            .unwrap_or_default();
+    }
+    if let Some(name) = &args.script {
+        std::process::exit(cli::run_script(
+            name,
+            args.confirm && !args.yes,
+            &execution,
+            &execution_log,
+        ));
+    }
+    if args.non_interactive {
+        std::process::exit(cli::run_from_stdin(&execution, &execution_log)?);
+    }
+    if args.list_scripts {
+        #[cfg(feature = "serde")]
+        if args.json {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(metadata::SCRIPT_METADATA)?
+            );
+            return Ok(());
+        }
+        for meta in metadata::SCRIPT_METADATA {
+            println!("{}", meta.path);
+        }
+        return Ok(());
+    }
+    #[cfg(feature = "serde")]
+    if args.json {
+        let system = system_info_or_exit();
+        println!("{}", serde_json::to_string_pretty(&system)?);
+        return Ok(());
+    }
+    if args.sysinfo {
+        println!("{}", system_info_or_exit());
+        return Ok(());
+    }
+    // Before the TUI takes over the terminal, so the errors can still be read
+    let mut builder = TuiApp::builder();
+    if args.detect_first {
+        builder = builder.system(system_info_or_exit()).unwrap_or_else(|err| {
+            eprintln!("Failed to start the TUI: {err}");
+            std::process::exit(1);
+        });
+    }
     if args.compat {
-        set_theme(0);
+        builder = builder.compat();
+    } else {
+        // Without a saved theme, it's the one in the config
+        match config::load_theme() {
+            Ok(Some(theme)) => builder = builder.theme(theme),
+            Ok(None) => {}
+            Err(err) => {
+                eprintln!("Failed to load the theme: {err}");
+                std::process::exit(1);
+            }
+        }
     }
+    let keys = KeyBindings::load().unwrap_or_else(|err| {
+        eprintln!("Failed to load the key bindings: {err}");
+        std::process::exit(1);
+    });
+    let history = History::load().unwrap_or_else(|err| {
+        eprintln!("Failed to load the history: {err}");
+        std::process::exit(1);
+    });
+    let bookmarks = BookmarkStore::load().unwrap_or_else(|err| {
+        eprintln!("Failed to load the favorites: {err}");
+        std::process::exit(1);
+    });
+    let app = builder
+        .config(config)
+        .and_then(|builder| builder.key_bindings(keys))
+        .and_then(|builder| builder.history(history).bookmarks(bookmarks).build())
+        .unwrap_or_else(|err| {
+            eprintln!("Failed to start the TUI: {err}");
+            std::process::exit(1);
+        });
 
     stdout().execute(EnterAlternateScreen)?;
     enable_raw_mode()?;
+    stdout().execute(EnableMouseCapture)?;
     let mut terminal = Terminal::new(CrosstermBackend::new(stdout()))?;
     terminal.clear()?;
 
-    run(&mut terminal)?;
+    run(&mut terminal, app, execution, &execution_log, &args)?;
 
     // restore terminal
     disable_raw_mode()?;
@@ -55,45 +237,382 @@ fn main() -> std::io::Result<()> {
     Ok(())
 }
 
-fn run<B: Backend>(terminal: &mut Terminal<B>) -> io::Result<()> {
-    let mut command_opt: Option<RunningCommand> = None;
+/// Detects the system, or exits with a readable message instead of a panic if it can't
+fn system_info_or_exit() -> System {
+    System::info().unwrap_or_else(|err| {
+        eprintln!("Failed to detect the system: {err}");
+        std::process::exit(1);
+    })
+}
+
+fn run<B: Backend>(
+    terminal: &mut Terminal<B>,
+    app: TuiApp,
+    mut execution: ExecutionConfig,
+    execution_log: &ExecutionLog,
+    args: &Args,
+) -> io::Result<()> {
+    let TuiApp {
+        mut system,
+        config,
+        keys,
+        theme,
+        list: mut custom_list,
+    } = app;
+    let keys = &keys;
+    let read_only = config.read_only;
+    set_theme(theme);
 
-    let mut custom_list = CustomList::new();
+    if let Some(system) = &system {
+        execution.env = running_command::script_env(system);
+    }
+    // Detecting reads a lot of files, so it's done while the TUI is already up, unless it was
+    // done before
+    let (detected_tx, detected) = mpsc::channel();
+    let mut spinner = system.is_none().then(|| {
+        thread::spawn(move || {
+            let system = System::info().ok();
+            let env = system
+                .as_ref()
+                .map(running_command::script_env)
+                .unwrap_or_default();
+            // The rest of what the status bar shows, so drawing it doesn't have to wait
+            if let Some(system) = &system {
+                system.pretty_name();
+                system.package_manager_locked();
+            }
+            let _ = detected_tx.send((system, env));
+        });
+        Spinner::new("Detecting the system")
+    });
+    // Dry run mode can be toggled, it only starts out the way the arguments say
+    let mut dry_run = args.dry_run;
+    let mut floats = FloatStack::default();
+    // The names of the commands in the command float, for the status bar
+    let mut running_names = String::new();
+    // Set until the command float is finished, and written to the execution log
+    let mut log_pending = false;
+    let mut shortcut_overlay = ShortcutOverlay::new();
+
+    // Upgrades can change the distribution while the TUI is up. Without the watch, it's only
+    // detected again after running a command
+    let (os_release_tx, os_release_changed) = mpsc::channel();
+    let _ = System::watch(os_release_tx);
+    // Saving the theme or the history can fail while the TUI is up, the status bar shows why
+    let mut save_error: Option<String> = None;
     loop {
+        if let Ok((detected_system, env)) = detected.try_recv() {
+            system = detected_system;
+            execution.env = env;
+            spinner = None;
+        }
+        if let Some(spinner) = &mut spinner {
+            spinner.tick();
+        }
+        if os_release_changed.try_recv().is_ok() {
+            match &mut system {
+                Some(system) => {
+                    let _ = system.refresh();
+                }
+                None => system = System::info().ok(),
+            }
+        }
+        floats.sync_log();
+        if log_pending {
+            if let Some(command) = floats.command_mut().filter(|command| command.is_finished()) {
+                log_pending = false;
+                let (exit_code, duration) = command.exit().expect("the command is finished");
+                if command.timed_out() {
+                    save_error = Some(format!(
+                        "{running_names} was killed, it ran for longer than {}s",
+                        duration.as_secs()
+                    ));
+                }
+                let entry = LogEntry::finished_now(running_names.clone(), exit_code, duration);
+                if let Err(err) = execution_log.append(entry) {
+                    save_error = Some(format!("Failed to write the run log: {err}"));
+                }
+                // The command could have installed a package manager, or upgraded the distribution.
+                // If os-release is gone, what was detected before is still the best guess
+                if let Some(system) = &mut system {
+                    let _ = system.refresh();
+                }
+                let post_run = PostRunFloat::new(
+                    running_names.clone(),
+                    exit_code,
+                    duration,
+                    command.timed_out(),
+                    command.output_lines(),
+                );
+                floats.sync_log();
+                floats.replace_command(Float::PostRun(post_run));
+            }
+        }
+
         // Always redraw
         terminal
             .draw(|frame| {
-                custom_list.draw(frame, frame.size());
-                if let Some(ref mut command) = &mut command_opt {
-                    command.draw(frame);
-                }
+                let [main_area, status_area] =
+                    Layout::vertical([Constraint::Min(1), Constraint::Length(1)])
+                        .areas(frame.size());
+                let theme = get_theme();
+                frame.render_widget(Block::default().style(theme.base_style()), frame.size());
+
+                custom_list.draw(frame, main_area, theme);
+                floats.draw(frame, main_area, theme);
+
+                // The shortcuts of whatever is on top
+                let shortcut_list = match floats.top() {
+                    Some(float) => float.content().get_shortcut_list(keys),
+                    None => custom_list.get_shortcut_list(keys),
+                };
+                shortcut_overlay.draw(frame, main_area, theme, shortcut_list);
+
+                let message = match (floats.command(), &save_error) {
+                    (Some(command), _) if !command.is_finished() => match command.attempt() {
+                        (_, 1) => format!("Running {running_names}"),
+                        (attempt, max_attempts) => {
+                            format!("Running {running_names}, attempt {attempt} of {max_attempts}")
+                        }
+                    },
+                    (_, Some(err)) => err.clone(),
+                    _ => "idle".to_string(),
+                };
+                let status = if dry_run {
+                    StatusBar::new(format!("[DRY RUN] {message}"))
+                } else {
+                    StatusBar::new(message)
+                };
+                status
+                    .detecting(spinner.as_ref())
+                    .draw(frame, status_area, theme, system.as_ref());
             })
             .unwrap();
 
         // Wait for an event
-        if !event::poll(Duration::from_millis(10))? {
-            continue;
-        }
-
-        // It's guaranteed that the `read()` won't block when the `poll()`
-        // function returns `true`
-        if let Event::Key(key) = event::read()? {
-            // We are only interested in Press and Repeat events
-            if key.kind != KeyEventKind::Press && key.kind != KeyEventKind::Repeat {
-                continue;
+        let answer = if !event::poll(Duration::from_millis(10))? {
+            // Nothing happened, but a prompt may still run out of time, or answer by itself
+            match floats.top_mut() {
+                Some(float) => float.content_mut().poll(),
+                None => continue,
             }
-            if let Some(ref mut command) = command_opt {
-                if command.handle_key_event(&key) {
-                    command_opt = None;
+        } else {
+            // It's guaranteed that the `read()` won't block when the `poll()`
+            // function returns `true`
+            let event = event::read()?;
+            if let Event::Key(key) = event {
+                // We are only interested in Press and Repeat events
+                if key.kind != KeyEventKind::Press && key.kind != KeyEventKind::Repeat {
+                    continue;
                 }
-            } else {
-                if key.code == KeyCode::Char('q') {
-                    return Ok(());
+            }
+            // A running command gets all the keys, and so does the search bar. The help has
+            // the keys once it's open
+            let help_allowed = !custom_list.is_filter_focused()
+                && match floats.top() {
+                    Some(Float::Help(_)) => false,
+                    Some(Float::Log(_)) => true,
+                    _ => floats.command().is_none_or(|c| c.is_finished()),
+                };
+            if is_key(&event, &keys.shortcuts) {
+                shortcut_overlay.toggle();
+                continue;
+            } else if is_ctrl_key(&event, &keys.toggle_theme) {
+                // There's nothing to toggle in compatibility mode
+                if let Some(theme) = ThemeChoice::current() {
+                    let theme = theme.toggled();
+                    set_theme(theme.index());
+                    save_error = config::save_theme(theme)
+                        .err()
+                        .map(|err| format!("Failed to save the theme: {err}"));
                 }
-                if let Some(cmd) = custom_list.handle_key(key) {
-                    command_opt = Some(RunningCommand::new(cmd));
+                continue;
+            } else if help_allowed && is_key(&event, &keys.help) {
+                let mut shortcuts = vec![custom_list.get_shortcut_list(keys)];
+                shortcuts.extend(floats.shortcut_lists(keys));
+                let help = HelpFloat::new(shortcuts);
+                floats.push(Float::Help(help));
+                continue;
+            } else if let Some(float) = floats.top_mut() {
+                float_event(float.content_mut(), &event, keys)
+            } else {
+                let nodes = match event {
+                    Event::Key(key) => {
+                        if keys.quit.contains(&key.code) && !custom_list.is_filter_focused() {
+                            return Ok(());
+                        }
+                        if keys.dry_run.contains(&key.code) && !custom_list.is_filter_focused() {
+                            dry_run = !dry_run;
+                            continue;
+                        }
+                        if is_ctrl_key(&event, &keys.log_history) {
+                            match execution_log.load() {
+                                Ok(entries) => {
+                                    floats.push(Float::RunLog(LogHistoryFloat::new(entries)))
+                                }
+                                Err(err) => {
+                                    save_error = Some(format!("Failed to read the run log: {err}"))
+                                }
+                            }
+                            continue;
+                        }
+                        let nodes = custom_list.handle_key(key, keys);
+                        if let Some(err) = custom_list.take_bookmark_error() {
+                            save_error = Some(format!("Failed to save the favorites: {err}"));
+                        }
+                        nodes
+                    }
+                    Event::Mouse(mouse) => custom_list.handle_mouse(mouse),
+                    _ => None,
+                };
+                // Those that need root, or commands that aren't there, would only fail once
+                // they're confirmed. Dry runs and read-only mode start nothing, so there's nothing
+                // to check
+                let checked = nodes.as_deref().filter(|_| !dry_run && !read_only);
+                let missing_root =
+                    checked.and_then(|nodes| nodes.iter().find(|node| node.missing_root()));
+                let missing_deps =
+                    checked.and_then(|nodes| MissingDepsFloat::check(nodes, system.as_ref()));
+                if spinner.is_some() && checked.is_some() {
+                    // The scripts would miss what they're told about the system
+                    save_error = Some("Wait until the system is detected".to_string());
+                } else if let Some(node) = missing_root {
+                    save_error = Some(format!(
+                        "{} only runs as root, start linutil with sudo",
+                        node.name
+                    ));
+                } else if let Some(missing_deps) = missing_deps {
+                    floats.push(Float::MissingDeps(missing_deps));
+                } else if let Some(params) = checked.and_then(ScriptParamFloat::new) {
+                    // Submitting the form is what runs them, there's no prompt
+                    if let Some(nodes) = nodes {
+                        floats.push(Float::Params(params, nodes));
+                    }
+                } else if let Some(nodes) = nodes {
+                    // A chain lists its steps, and what undoes them
+                    let chain = ScriptChain::new(nodes.clone());
+                    let steps = chain.describe_steps();
+                    let names: Vec<&str> = if chain.is_chain() {
+                        steps.iter().map(String::as_str).collect()
+                    } else {
+                        nodes.iter().map(|node| node.name).collect()
+                    };
+                    let prompt: Box<dyn FloatContent> = match args.confirm_timeout {
+                        _ if read_only && !dry_run => Box::new(ReadOnlyNotice::new(keys)),
+                        // Answered the next time there are no events, which is right away
+                        _ if args.yes => Box::new(NonInteractiveOutput { auto_confirm: true }),
+                        Some(seconds) => Box::new(ConfirmPrompt::with_timeout(&names, seconds)),
+                        None => Box::new(ConfirmPrompt::new(&names)),
+                    };
+                    floats.push(Float::Prompt(prompt, nodes));
                 }
+                continue;
+            }
+        };
+
+        // What the float on top answered
+        match answer {
+            FloatEvent::CloseFloat | FloatEvent::AbortConfirmation => {
+                floats.pop();
             }
+            FloatEvent::Resize => terminal.clear()?,
+            FloatEvent::ShowLog => {
+                let log = match floats.top() {
+                    Some(Float::PostRun(post_run)) => {
+                        LogViewerFloat::finished(post_run.lines().to_vec())
+                    }
+                    _ => LogViewerFloat::default(),
+                };
+                floats.push(Float::Log(log));
+            }
+            FloatEvent::ConfirmSelection => match floats.pop() {
+                Some(Float::Prompt(_, nodes)) if dry_run => {
+                    floats.push(Float::DryRun(DryRunFloat::new(&nodes, system.as_ref())));
+                }
+                Some(Float::Prompt(_, nodes)) => {
+                    running_names = node_names(&nodes);
+                    log_pending = true;
+                    let command = run_nodes(&nodes, &execution, &mut custom_list, &mut save_error);
+                    floats.push(Float::Command(command));
+                }
+                Some(Float::MissingDeps(missing_deps)) => {
+                    if let Some(install) = missing_deps.install_command() {
+                        running_names = format!("Installing {}", missing_deps.missing().join(", "));
+                        log_pending = true;
+                        let command = RunningCommand::new(&[&install], &execution);
+                        floats.push(Float::Command(command));
+                    }
+                }
+                // Stays open until all of the values are valid
+                Some(Float::Params(mut params, nodes)) => match params.env() {
+                    Some(env) => {
+                        let mut execution = execution.clone();
+                        execution.env.extend(env);
+                        running_names = node_names(&nodes);
+                        log_pending = true;
+                        let command =
+                            run_nodes(&nodes, &execution, &mut custom_list, &mut save_error);
+                        floats.push(Float::Command(command));
+                    }
+                    None => floats.push(Float::Params(params, nodes)),
+                },
+                Some(float) => floats.push(float),
+                None => {}
+            },
+            FloatEvent::None => {}
         }
     }
 }
+
+/// Start the confirmed commands, which also forgets what was picked in the list, and puts them
+/// in the history
+fn run_nodes(
+    nodes: &[ListNode],
+    execution: &ExecutionConfig,
+    custom_list: &mut CustomList,
+    save_error: &mut Option<String>,
+) -> RunningCommand {
+    let command = ScriptChain::new(nodes.to_vec()).command();
+    // They run in one shell, so they're tried again together
+    let execution = ExecutionConfig {
+        retries: nodes.iter().map(ListNode::retries).max().unwrap_or(0),
+        timeout: running_command::script_timeout(
+            nodes.iter().map(ListNode::timeout),
+            execution.timeout,
+        ),
+        ..execution.clone()
+    };
+    custom_list.clear_selection();
+    *save_error = custom_list
+        .record_run(nodes)
+        .err()
+        .map(|err| format!("Failed to save the history: {err}"));
+    RunningCommand::new(&[&command], &execution)
+}
+
+/// The names of the nodes, for showing them in a single line
+fn node_names(nodes: &[ListNode]) -> String {
+    let names: Vec<&str> = nodes.iter().map(|node| node.name).collect();
+    names.join(", ")
+}
+
+/// Whether the event is a press of one of `keys`
+fn is_key(event: &Event, keys: &[KeyCode]) -> bool {
+    matches!(event, Event::Key(key) if keys.contains(&key.code))
+}
+
+/// Whether the event is a press of one of `keys` together with Ctrl
+fn is_ctrl_key(event: &Event, keys: &[KeyCode]) -> bool {
+    matches!(event, Event::Key(key) if key.modifiers.contains(KeyModifiers::CONTROL) && keys.contains(&key.code))
+}
+
+/// Pass a key press or a mouse event on to a float. A resize doesn't need the float itself, it
+/// gets the new area the next time it's drawn
+fn float_event(float: &mut dyn FloatContent, event: &Event, keys: &KeyBindings) -> FloatEvent {
+    match event {
+        Event::Key(key) => float.handle_key_event(key, keys),
+        Event::Mouse(mouse) => float.handle_mouse_event(mouse),
+        Event::Resize(..) => FloatEvent::Resize,
+        _ => FloatEvent::None,
+    }
+}
@@ -0,0 +1,113 @@
+use serde::Serialize;
+
+/// What the header of a script in `src/commands` says about it, read by the build script
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub struct ScriptMeta {
+    /// Relative to `src/commands`
+    pub path: &'static str,
+    pub description: &'static str,
+    pub author: &'static str,
+    pub tags: &'static [&'static str],
+    /// Commands that have to be on `PATH` before the script can run
+    pub requires: &'static [&'static str],
+    pub privilege: PrivilegeMode,
+    /// What's asked for before the script runs
+    pub params: &'static [ScriptParam],
+    /// How many more times the script is run if it fails
+    pub retry: u32,
+    /// How many seconds the script may run for, instead of the timeout in the config
+    pub timeout: Option<u64>,
+    /// What undoes the script, if it or one that runs after it fails
+    pub rollback: Option<Rollback>,
+}
+
+/// A script that undoes another one, from `# @rollback other-script.sh` in its header
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub struct Rollback {
+    /// Relative to `src/commands`
+    pub path: &'static str,
+    /// Without the common script
+    #[serde(skip)]
+    pub command: &'static str,
+}
+
+/// A value the script needs, from a `# @param name:kind "Label"` in its header. It gets it in
+/// `LINUTIL_PARAM_<NAME>`
+// None of the scripts has one yet, the generated metadata is the only place they're made
+#[allow(dead_code)]
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub struct ScriptParam {
+    pub name: &'static str,
+    pub kind: ParamKind,
+    pub label: &'static str,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ParamKind {
+    /// Anything but nothing
+    String,
+    /// A whole number, 0 or more
+    Int,
+    /// A path whose directory exists. `~` is the home directory
+    Path,
+    /// Yes or no
+    Bool,
+}
+
+impl ScriptParam {
+    /// The variable the script gets the value in
+    pub fn env_name(&self) -> String {
+        format!("LINUTIL_PARAM_{}", self.name.to_ascii_uppercase())
+    }
+}
+
+/// Who a script runs as, from `# @privilege` in its header
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PrivilegeMode {
+    /// `user`, or no `@privilege` at all: whoever started linutil
+    #[default]
+    AsUser,
+    /// `sudo`: root, sudo is put in front of the script unless linutil already runs as root
+    // None of the scripts asks for it yet, the generated metadata is the only place it's made
+    #[allow(dead_code)]
+    AutoSudo,
+    /// `root`: root, the script isn't started unless linutil already runs as root
+    RequireSudo,
+}
+
+include!(concat!(env!("OUT_DIR"), "/scripts_metadata.rs"));
+
+/// The metadata of the script at `path`, relative to `src/commands`
+pub fn find(path: &str) -> Option<&'static ScriptMeta> {
+    SCRIPT_METADATA.iter().find(|meta| meta.path == path)
+}
+
+/// Every tag of every script, once, in the order they first show up in
+pub fn all_tags() -> Vec<&'static str> {
+    let mut tags = Vec::new();
+    for &tag in SCRIPT_METADATA.iter().flat_map(|meta| meta.tags) {
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    tags
+}
+
+#[test]
+fn test_script_metadata() {
+    if option_env!("LINUTIL_NOSCRIPTS").is_some_and(|value| !value.is_empty()) {
+        assert!(SCRIPT_METADATA.is_empty());
+        return;
+    }
+    let update = find("system-update.sh").unwrap();
+    assert!(!update.description.is_empty());
+    assert!(find("common-script.sh").is_none());
+
+    let tags = all_tags();
+    for tag in update.tags {
+        assert_eq!(tags.iter().filter(|&other| other == tag).count(), 1);
+    }
+}
@@ -0,0 +1,63 @@
+//! The package managers of programming languages. They're there next to the one of the system,
+//! for setting up development environments
+
+use std::{collections::HashMap, env, ffi::OsStr};
+
+use super::is_in_path;
+
+/// The package managers, with the binaries any of which counts as having it
+pub const LANGUAGE_MANAGERS: [(&str, &[&str]); 7] = [
+    ("cargo", &["cargo"]),
+    ("pip", &["pip", "pip3"]),
+    ("npm", &["npm"]),
+    ("yarn", &["yarn"]),
+    ("pnpm", &["pnpm"]),
+    ("gem", &["gem"]),
+    ("composer", &["composer"]),
+];
+
+/// Whether each of `LANGUAGE_MANAGERS` is on `PATH`
+pub fn detect_language_managers() -> HashMap<&'static str, bool> {
+    detect_in(&env::var_os("PATH").unwrap_or_default())
+}
+
+fn detect_in(path: &OsStr) -> HashMap<&'static str, bool> {
+    LANGUAGE_MANAGERS
+        .iter()
+        .map(|&(name, binaries)| {
+            let found = binaries.iter().any(|binary| is_in_path(binary, path));
+            (name, found)
+        })
+        .collect()
+}
+
+/// The variable scripts can check for the package manager, `LINUTIL_HAS_CARGO` for cargo
+pub fn env_name(name: &str) -> String {
+    format!("LINUTIL_HAS_{}", name.to_ascii_uppercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, os::unix::fs::PermissionsExt};
+
+    use super::*;
+    use crate::test_dir::TestDir;
+
+    #[test]
+    fn language_managers_are_found() {
+        let dir = TestDir::new("langtools");
+        for binary in ["cargo", "pip3"] {
+            let path = dir.join(binary);
+            fs::write(&path, "").unwrap();
+            fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+        }
+
+        let found = detect_in(dir.as_os_str());
+        assert_eq!(found.len(), LANGUAGE_MANAGERS.len());
+        assert!(found["cargo"]);
+        assert!(found["pip"]);
+        assert!(!found["npm"]);
+        assert!(!found["composer"]);
+        assert_eq!(env_name("pnpm"), "LINUTIL_HAS_PNPM");
+    }
+}
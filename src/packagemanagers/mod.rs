@@ -0,0 +1,633 @@
+use std::{
+    borrow::Cow,
+    env,
+    ffi::OsStr,
+    fmt, fs,
+    os::{fd::AsRawFd, unix::fs::PermissionsExt},
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
+
+pub mod langtools;
+
+/// A package manager we know how to drive
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PackageManager {
+    Apt,
+    Dnf,
+    Pacman,
+    Zypper,
+    Xbps,
+    Apk,
+    Emerge,
+    NixEnv,
+    Eopkg,
+    Flatpak,
+    Snap,
+    /// Integrates AppImages into the desktop. Its "packages" are the paths to the AppImages
+    AppImageLauncher,
+    /// Homebrew on Linux, which is often not on `PATH` of scripts that run as root
+    Homebrew,
+    /// The `nix` command, on any distribution. Its packages are flake references, like
+    /// `nixpkgs#git`. NixOS itself is driven with `nix-env`
+    Nix,
+}
+
+/// How a package manager shows that another process is using it
+enum Lock {
+    /// The file only exists while it's in use
+    File(&'static str),
+    /// The file is there all the time, a `fcntl(2)` lock on it is held while it's in use
+    Fcntl(&'static str),
+    /// The file names the process that uses it, it can outlive that process
+    Pid(&'static str),
+}
+
+/// Package managers that install applications next to the ones of the distribution, and so can be
+/// there on any of them
+const ADDITIONAL_PACKAGE_MANAGERS: [PackageManager; 3] = [
+    PackageManager::Flatpak,
+    PackageManager::Snap,
+    PackageManager::AppImageLauncher,
+];
+
+/// Maps the os-release `ID` of a distribution to its native package manager
+const PACKAGE_MANAGERS: [(&str, PackageManager); 9] = [
+    ("fedora", PackageManager::Dnf),
+    ("debian", PackageManager::Apt),
+    ("arch", PackageManager::Pacman),
+    ("opensuse", PackageManager::Zypper),
+    ("void", PackageManager::Xbps),
+    ("alpine", PackageManager::Apk),
+    ("gentoo", PackageManager::Emerge),
+    ("nixos", PackageManager::NixEnv),
+    ("solus", PackageManager::Eopkg),
+];
+
+/// `ID`s of editions of a distribution, that should be treated like the distribution itself
+const ID_ALIASES: [(&str, &str); 6] = [
+    ("opensuse-tumbleweed", "opensuse"),
+    ("opensuse-leap", "opensuse"),
+    ("opensuse-slowroll", "opensuse"),
+    ("opensuse-microos", "opensuse"),
+    ("archarm", "arch"),
+    ("fedora-asahi-remix", "fedora"),
+];
+
+/// Suffixes that name a release channel of a distribution, rather than a distribution of its own
+const RELEASE_SUFFIXES: [&str; 6] = ["lts", "stable", "testing", "unstable", "rolling", "edge"];
+
+impl PackageManager {
+    /// The name of the executable, e.g. `pacman`
+    pub fn binary_name(&self) -> &'static str {
+        match self {
+            Self::Apt => "apt-get",
+            Self::Dnf => "dnf",
+            Self::Pacman => "pacman",
+            Self::Zypper => "zypper",
+            Self::Xbps => "xbps-install",
+            Self::Apk => "apk",
+            Self::Emerge => "emerge",
+            Self::NixEnv => "nix-env",
+            Self::Eopkg => "eopkg",
+            Self::Flatpak => "flatpak",
+            Self::Snap => "snap",
+            Self::AppImageLauncher => "ail-cli",
+            Self::Homebrew => "brew",
+            Self::Nix => "nix",
+        }
+    }
+
+    /// The arguments that, followed by package names, install packages without asking for
+    /// confirmation
+    pub fn install_args(&self) -> &'static [&'static str] {
+        match self {
+            Self::Apt => &["install", "-y"],
+            Self::Dnf => &["install", "-y"],
+            Self::Pacman => &["-S", "--noconfirm"],
+            Self::Zypper => &["--non-interactive", "install"],
+            Self::Xbps => &["-Sy"],
+            Self::Apk => &["add"],
+            Self::Emerge => &["--ask=n"],
+            Self::NixEnv => &["-i"],
+            Self::Eopkg => &["install", "-y"],
+            Self::Flatpak => &["install", "-y"],
+            Self::Snap => &["install"],
+            Self::AppImageLauncher => &["integrate"],
+            Self::Homebrew => &["install"],
+            Self::Nix => &["profile", "install"],
+        }
+    }
+
+    /// The arguments that upgrade the given packages, or every installed package if none are given.
+    /// AppImageLauncher can't update AppImages, it has none
+    pub fn update_args(&self) -> &'static [&'static str] {
+        match self {
+            Self::Apt => &["upgrade", "-y"],
+            Self::Dnf => &["upgrade", "-y"],
+            Self::Pacman => &["-Syu", "--noconfirm"],
+            Self::Zypper => &["--non-interactive", "update"],
+            Self::Xbps => &["-Syu"],
+            Self::Apk => &["upgrade"],
+            Self::Emerge => &["--ask=n", "--update", "--deep", "--newuse"],
+            Self::NixEnv => &["-u"],
+            Self::Eopkg => &["upgrade", "-y"],
+            Self::Flatpak => &["update", "-y"],
+            Self::Snap => &["refresh"],
+            Self::AppImageLauncher => &[],
+            Self::Homebrew => &["upgrade"],
+            Self::Nix => &["profile", "upgrade"],
+        }
+    }
+
+    /// The arguments that, followed by package names, remove packages. On Void these have to be
+    /// passed to `remove_binary_name()`, since `xbps-install` can't remove anything
+    pub fn remove_args(&self) -> &'static [&'static str] {
+        match self {
+            Self::Apt => &["remove", "-y"],
+            Self::Dnf => &["remove", "-y"],
+            Self::Pacman => &["-Rns", "--noconfirm"],
+            Self::Zypper => &["--non-interactive", "remove"],
+            Self::Xbps => &["-Ry"],
+            Self::Apk => &["del"],
+            Self::Emerge => &["--ask=n", "--depclean"],
+            Self::NixEnv => &["-e"],
+            Self::Eopkg => &["remove", "-y"],
+            Self::Flatpak => &["uninstall", "-y"],
+            Self::Snap => &["remove"],
+            Self::AppImageLauncher => &["unintegrate"],
+            Self::Homebrew => &["uninstall"],
+            Self::Nix => &["profile", "remove"],
+        }
+    }
+
+    /// Whether the executable of this package manager can be found on `PATH`. It might not be,
+    /// even on the distribution it belongs to, e.g. in containers and chroots
+    pub fn is_available(&self) -> bool {
+        env::var_os("PATH").is_some_and(|path| is_in_path(self.binary_name(), &path))
+    }
+
+    /// Whether another process is using the package manager right now, so running it would fail.
+    /// Package managers without a lock we know of are never locked. Neither are ones whose lock
+    /// can't be read, like the one of apt is for anyone but root
+    pub fn is_locked(&self) -> bool {
+        self.is_locked_in(Path::new("/"))
+    }
+
+    /// Like `is_locked()`, with the lock files under `root` instead of `/`
+    fn is_locked_in(&self, root: &Path) -> bool {
+        let under_root = |path: &str| root.join(path.trim_start_matches('/'));
+        match self.lock() {
+            Some(Lock::File(path)) => under_root(path).exists(),
+            Some(Lock::Fcntl(path)) => is_fcntl_locked(&under_root(path)),
+            Some(Lock::Pid(path)) => is_pid_locked(&under_root(path)),
+            None => false,
+        }
+    }
+
+    fn lock(&self) -> Option<Lock> {
+        match self {
+            Self::Apt => Some(Lock::Fcntl("/var/lib/dpkg/lock-frontend")),
+            Self::Dnf => Some(Lock::Pid("/var/lib/dnf/rpmdb_lock.pid")),
+            Self::Pacman => Some(Lock::File("/var/lib/pacman/db.lck")),
+            Self::Zypper => Some(Lock::Pid("/run/zypp.pid")),
+            _ => None,
+        }
+    }
+
+    /// How many packages are installed with this package manager. Read from its database where
+    /// that's simple enough, otherwise it's asked. `None` if we don't know how, or it failed
+    pub fn installed_count(&self) -> Option<usize> {
+        match self {
+            Self::Apt => {
+                let status = fs::read_to_string("/var/lib/dpkg/status").ok()?;
+                Some(count_dpkg_installed(&status))
+            }
+            // Every package has a directory of its own, next to a file with the version of the
+            // database
+            Self::Pacman => count_dirs(Path::new("/var/lib/pacman/local")),
+            Self::Eopkg => count_dirs(Path::new("/var/lib/eopkg/package")),
+            Self::Emerge => {
+                let categories = fs::read_dir("/var/db/pkg").ok()?;
+                categories
+                    .flatten()
+                    .map(|category| count_dirs(&category.path()))
+                    .sum()
+            }
+            Self::Apk => {
+                let installed = fs::read_to_string("/lib/apk/db/installed").ok()?;
+                Some(
+                    installed
+                        .lines()
+                        .filter(|line| line.starts_with("P:"))
+                        .count(),
+                )
+            }
+            Self::Dnf | Self::Zypper => count_output_lines("rpm", &["-qa"]),
+            Self::Xbps => count_output_lines("xbps-query", &["-l"]),
+            Self::Flatpak => count_output_lines("flatpak", &["list"]),
+            Self::Snap => count_output_lines("snap", &["list"]).map(|rows| rows.saturating_sub(1)),
+            Self::NixEnv => count_output_lines("nix-env", &["-q"]),
+            Self::Homebrew => count_output_lines("brew", &["list", "-1"]),
+            Self::AppImageLauncher | Self::Nix => None,
+        }
+    }
+
+    /// The executable `remove_args()` are passed to
+    pub fn remove_binary_name(&self) -> &'static str {
+        match self {
+            Self::Xbps => "xbps-remove",
+            _ => self.binary_name(),
+        }
+    }
+}
+
+impl fmt::Display for PackageManager {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.binary_name())
+    }
+}
+
+/// Normalizes an os-release `ID` to the distribution it's an edition or release of, so
+/// `ubuntu-22.04` and `ubuntu-lts` both become `ubuntu`, and `opensuse-tumbleweed` becomes
+/// `opensuse`, like in the table of `get()`. Unknown IDs are only lowercased, `MyDistro` becomes
+/// `mydistro`. IDs that need no normalizing are returned as they are, without allocating
+pub fn normalize_id(id: &str) -> Cow<'_, str> {
+    // Version numbers and release channels are only ever at the end
+    let mut base = id;
+    while let Some((rest, suffix)) = base.rsplit_once('-') {
+        let is_version = suffix.starts_with(|c: char| c.is_ascii_digit());
+        if !is_version && !RELEASE_SUFFIXES.contains(&&*suffix.to_ascii_lowercase()) {
+            break;
+        }
+        base = rest;
+    }
+    let known = ID_ALIASES
+        .iter()
+        .find(|(alias, _)| alias.eq_ignore_ascii_case(base))
+        .map(|(_, id)| *id)
+        .or_else(|| {
+            PACKAGE_MANAGERS
+                .iter()
+                .map(|(id, _)| *id)
+                .find(|id| id.eq_ignore_ascii_case(base))
+        });
+    match known {
+        Some(known) => Cow::Borrowed(known),
+        None if base.chars().any(|c| c.is_ascii_uppercase()) => {
+            Cow::Owned(base.to_ascii_lowercase())
+        }
+        None => Cow::Borrowed(base),
+    }
+}
+
+/// Returns the native package manager of the distribution with the given os-release `ID`, which
+/// has to be normalized already, see `normalize_id()`
+pub fn get(id: &str) -> Option<PackageManager> {
+    PACKAGE_MANAGERS
+        .iter()
+        .find(|(distro, _)| *distro == id)
+        .map(|(_, package_manager)| *package_manager)
+}
+
+/// The package managers for applications that are installed, besides the one of the distribution
+pub fn get_additional_managers() -> Vec<PackageManager> {
+    let mut package_managers = env::var_os("PATH")
+        .map(|path| additional_managers_in(&path))
+        .unwrap_or_default();
+    package_managers.extend(detect_homebrew());
+    package_managers.extend(detect_nix());
+    package_managers
+}
+
+fn additional_managers_in(path: &OsStr) -> Vec<PackageManager> {
+    ADDITIONAL_PACKAGE_MANAGERS
+        .into_iter()
+        .filter(|package_manager| is_in_path(package_manager.binary_name(), path))
+        .collect()
+}
+
+/// Looks for `brew` in `$HOMEBREW_PREFIX`, then in the places Homebrew installs itself to. It isn't
+/// looked for on `PATH`, its shell setup is often only done for interactive shells
+pub fn detect_homebrew() -> Option<PackageManager> {
+    let prefix = env::var_os("HOMEBREW_PREFIX").filter(|prefix| !prefix.is_empty());
+    let home = env::var_os("HOME").filter(|home| !home.is_empty());
+    homebrew_in(prefix.as_deref(), home.as_deref())
+}
+
+fn homebrew_in(prefix: Option<&OsStr>, home: Option<&OsStr>) -> Option<PackageManager> {
+    let prefixes = [
+        prefix.map(PathBuf::from),
+        home.map(|home| Path::new(home).join(".linuxbrew")),
+        Some(PathBuf::from("/home/linuxbrew/.linuxbrew")),
+    ];
+    prefixes
+        .into_iter()
+        .flatten()
+        .any(|prefix| is_executable(&prefix.join("bin/brew")))
+        .then_some(PackageManager::Homebrew)
+}
+
+/// Nix needs its store as well as the `nix` command, the command alone can't install anything
+pub fn detect_nix() -> Option<PackageManager> {
+    let path = env::var_os("PATH")?;
+    nix_in(Path::new("/nix/store"), &path)
+}
+
+fn nix_in(store: &Path, path: &OsStr) -> Option<PackageManager> {
+    (store.is_dir() && is_in_path(PackageManager::Nix.binary_name(), path))
+        .then_some(PackageManager::Nix)
+}
+
+/// Asks the kernel whether a process holds a lock on the file, without taking one
+fn is_fcntl_locked(path: &Path) -> bool {
+    let Ok(file) = fs::File::open(path) else {
+        return false;
+    };
+    // SAFETY: all zeroes is a valid `flock`, the fields that matter are set below
+    let mut lock: libc::flock = unsafe { std::mem::zeroed() };
+    lock.l_type = libc::F_WRLCK as libc::c_short;
+    lock.l_whence = libc::SEEK_SET as libc::c_short;
+    // SAFETY: the descriptor is open for as long as `file` lives, and `lock` outlives the call
+    let result = unsafe { libc::fcntl(file.as_raw_fd(), libc::F_GETLK, &mut lock) };
+    result == 0 && lock.l_type != libc::F_UNLCK as libc::c_short
+}
+
+/// The file is left behind by package managers that crash, so the process has to still be running
+fn is_pid_locked(path: &Path) -> bool {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|pid| pid.trim().parse::<u32>().ok())
+        .is_some_and(|pid| Path::new(&format!("/proc/{pid}")).exists())
+}
+
+/// Counts the packages dpkg has installed, leaving out the ones that were removed but left their
+/// configuration behind
+fn count_dpkg_installed(status: &str) -> usize {
+    status
+        .lines()
+        .filter(|line| {
+            line.strip_prefix("Status:")
+                .is_some_and(|status| status.trim().ends_with(" installed"))
+        })
+        .count()
+}
+
+fn count_dirs(dir: &Path) -> Option<usize> {
+    let entries = fs::read_dir(dir).ok()?;
+    Some(
+        entries
+            .flatten()
+            .filter(|entry| entry.file_type().is_ok_and(|file_type| file_type.is_dir()))
+            .count(),
+    )
+}
+
+fn count_output_lines(program: &str, args: &[&str]) -> Option<usize> {
+    let output = Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()
+        .filter(|output| output.status.success())?;
+    Some(
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .count(),
+    )
+}
+
+/// Checks every entry of a `PATH`-like list of directories for an executable file with this name
+pub fn is_in_path(binary: &str, path: &OsStr) -> bool {
+    env::split_paths(path).any(|dir| is_executable(&dir.join(binary)))
+}
+
+pub(crate) fn is_executable(path: &Path) -> bool {
+    fs::metadata(path)
+        .is_ok_and(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_dir::TestDir;
+
+    #[test]
+    fn known_ids_map_to_package_managers() {
+        let expected = [
+            ("fedora", "dnf"),
+            ("debian", "apt-get"),
+            ("arch", "pacman"),
+            ("opensuse", "zypper"),
+            ("void", "xbps-install"),
+            ("alpine", "apk"),
+            ("gentoo", "emerge"),
+            ("nixos", "nix-env"),
+            ("solus", "eopkg"),
+        ];
+        for (id, name) in expected {
+            assert_eq!(get(id).map(|pm| pm.binary_name()), Some(name));
+        }
+    }
+
+    #[test]
+    fn unknown_id_has_no_package_manager() {
+        assert_eq!(get("templeos"), None);
+    }
+
+    #[test]
+    fn install_commands_are_assembled() {
+        let expected = [
+            (PackageManager::Apt, "apt-get install -y git"),
+            (PackageManager::Dnf, "dnf install -y git"),
+            (PackageManager::Pacman, "pacman -S --noconfirm git"),
+            (
+                PackageManager::Zypper,
+                "zypper --non-interactive install git",
+            ),
+            (PackageManager::Xbps, "xbps-install -Sy git"),
+            (PackageManager::Apk, "apk add git"),
+            (PackageManager::Emerge, "emerge --ask=n git"),
+            (PackageManager::NixEnv, "nix-env -i git"),
+            (PackageManager::Eopkg, "eopkg install -y git"),
+            (PackageManager::Flatpak, "flatpak install -y git"),
+            (PackageManager::Snap, "snap install git"),
+            (PackageManager::AppImageLauncher, "ail-cli integrate git"),
+            (PackageManager::Homebrew, "brew install git"),
+            (PackageManager::Nix, "nix profile install git"),
+        ];
+        for (package_manager, command) in expected {
+            let assembled = [package_manager.binary_name()]
+                .iter()
+                .chain(package_manager.install_args())
+                .chain(&["git"])
+                .copied()
+                .collect::<Vec<_>>()
+                .join(" ");
+            assert_eq!(assembled, command);
+        }
+    }
+
+    #[test]
+    fn binaries_are_found_in_path() {
+        let dir = TestDir::new("path");
+        let executable = dir.join("pacman");
+        fs::write(&executable, "#!/bin/sh\n").unwrap();
+        fs::set_permissions(&executable, fs::Permissions::from_mode(0o755)).unwrap();
+        let not_executable = dir.join("dnf");
+        fs::write(&not_executable, "").unwrap();
+        fs::set_permissions(&not_executable, fs::Permissions::from_mode(0o644)).unwrap();
+
+        let path = env::join_paths([Path::new("/nonexistent"), &dir]).unwrap();
+        assert!(is_in_path("pacman", &path));
+        assert!(!is_in_path("dnf", &path));
+        assert!(!is_in_path("apt-get", &path));
+    }
+
+    #[test]
+    fn additional_managers_are_found_in_path() {
+        let dir = TestDir::new("additional");
+        for binary in ["snap", "flatpak", "pacman"] {
+            let executable = dir.join(binary);
+            fs::write(&executable, "#!/bin/sh\n").unwrap();
+            fs::set_permissions(&executable, fs::Permissions::from_mode(0o755)).unwrap();
+        }
+
+        // In the order they're checked in, and without the system package manager
+        assert_eq!(
+            additional_managers_in(dir.as_os_str()),
+            [PackageManager::Flatpak, PackageManager::Snap]
+        );
+        assert!(additional_managers_in(OsStr::new("/nonexistent")).is_empty());
+    }
+
+    #[test]
+    fn homebrew_is_found_in_its_prefixes() {
+        let home = TestDir::new("homebrew");
+        let bin = home.join(".linuxbrew/bin");
+        fs::create_dir_all(&bin).unwrap();
+        fs::write(bin.join("brew"), "#!/bin/sh\n").unwrap();
+        fs::set_permissions(bin.join("brew"), fs::Permissions::from_mode(0o755)).unwrap();
+
+        assert_eq!(
+            homebrew_in(None, Some(home.as_os_str())),
+            Some(PackageManager::Homebrew)
+        );
+        assert_eq!(
+            homebrew_in(Some(home.join(".linuxbrew").as_os_str()), None),
+            Some(PackageManager::Homebrew)
+        );
+        if !Path::new("/home/linuxbrew/.linuxbrew/bin/brew").exists() {
+            assert_eq!(homebrew_in(Some(home.as_os_str()), None), None);
+        }
+    }
+
+    #[test]
+    fn nix_needs_its_store_and_binary() {
+        let dir = TestDir::new("nix");
+        let store = dir.join("store");
+        let bin = dir.join("bin");
+        fs::create_dir_all(&bin).unwrap();
+        fs::write(bin.join("nix"), "#!/bin/sh\n").unwrap();
+        fs::set_permissions(bin.join("nix"), fs::Permissions::from_mode(0o755)).unwrap();
+
+        assert_eq!(nix_in(&store, bin.as_os_str()), None);
+        fs::create_dir(&store).unwrap();
+        assert_eq!(nix_in(&store, bin.as_os_str()), Some(PackageManager::Nix));
+        assert_eq!(nix_in(&store, OsStr::new("/nonexistent")), None);
+        assert_ne!(get("nixos"), Some(PackageManager::Nix));
+    }
+
+    #[test]
+    fn locks_are_detected() {
+        let dir = TestDir::new("lock");
+
+        let pid_file = dir.join("pm.pid");
+        assert!(!is_pid_locked(&pid_file));
+        fs::write(&pid_file, format!("{}\n", std::process::id())).unwrap();
+        assert!(is_pid_locked(&pid_file));
+        fs::write(&pid_file, "").unwrap();
+        assert!(!is_pid_locked(&pid_file));
+
+        let lock_file = dir.join("lock");
+        assert!(!is_fcntl_locked(&lock_file));
+        fs::write(&lock_file, "").unwrap();
+        assert!(!is_fcntl_locked(&lock_file));
+    }
+
+    #[test]
+    fn held_fcntl_lock_is_detected() {
+        let dir = TestDir::new("fcntl-lock");
+        let lock_file = dir.join("var/lib/dpkg/lock-frontend");
+        fs::create_dir_all(lock_file.parent().unwrap()).unwrap();
+        let held = fs::File::create(&lock_file).unwrap();
+        assert!(!PackageManager::Apt.is_locked_in(&dir));
+
+        // `F_GETLK` leaves out the locks of the process that asks, so a plain `F_SETLK` here would
+        // go unseen. An open file description lock belongs to the descriptor instead
+        // SAFETY: all zeroes is a valid `flock`, `l_pid` has to be 0 for this kind of lock
+        let mut lock: libc::flock = unsafe { std::mem::zeroed() };
+        lock.l_type = libc::F_WRLCK as libc::c_short;
+        lock.l_whence = libc::SEEK_SET as libc::c_short;
+        // SAFETY: `held` is open until the end of the test, and `lock` outlives the call
+        let result = unsafe { libc::fcntl(held.as_raw_fd(), libc::F_OFD_SETLK, &lock) };
+        assert_eq!(result, 0);
+        assert!(PackageManager::Apt.is_locked_in(&dir));
+
+        drop(held);
+        assert!(!PackageManager::Apt.is_locked_in(&dir));
+    }
+
+    #[test]
+    fn ids_are_normalized() {
+        let expected = [
+            ("ubuntu-22.04", "ubuntu"),
+            ("ubuntu-lts", "ubuntu"),
+            ("ubuntu", "ubuntu"),
+            ("debian-12-stable", "debian"),
+            ("opensuse-tumbleweed", "opensuse"),
+            ("Fedora-40", "fedora"),
+            ("archarm", "arch"),
+            ("linuxmint", "linuxmint"),
+            ("MyDistro", "mydistro"),
+            ("Ubuntu-22.04", "ubuntu"),
+            ("", ""),
+        ];
+        for (id, normalized) in expected {
+            assert_eq!(normalize_id(id), normalized);
+        }
+        assert!(matches!(
+            normalize_id("mydistro-2"),
+            Cow::Borrowed("mydistro")
+        ));
+        assert_eq!(get("Fedora"), None);
+        assert_eq!(
+            get(&normalize_id("opensuse-leap")),
+            Some(PackageManager::Zypper)
+        );
+    }
+
+    #[test]
+    fn dpkg_installed_packages_are_counted() {
+        let status = "\
+Package: bash
+Status: install ok installed
+
+Package: nano
+Status: deinstall ok config-files
+
+Package: git
+Status: install ok installed
+";
+        assert_eq!(count_dpkg_installed(status), 2);
+    }
+
+    #[test]
+    fn xbps_removes_with_its_own_binary() {
+        assert_eq!(PackageManager::Xbps.remove_binary_name(), "xbps-remove");
+        assert_eq!(PackageManager::Pacman.remove_binary_name(), "pacman");
+    }
+}
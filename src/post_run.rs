@@ -0,0 +1,143 @@
+use std::time::Duration;
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::{Alignment, Rect},
+    style::{Style, Stylize},
+    text::{Line, Span},
+    widgets::{Clear, Paragraph},
+    Frame,
+};
+
+use crate::{
+    config::KeyBindings,
+    float::{FloatContent, FloatEvent},
+    hint::Shortcut,
+    theme::Theme,
+};
+
+/// How many of the last lines of the output are shown
+const TAIL_LINES: usize = 10;
+
+/// How the commands went, shown once they're finished, instead of the terminal they ran in
+pub struct PostRunFloat {
+    names: String,
+    exit_code: u32,
+    duration: Duration,
+    timed_out: bool,
+    /// The whole output, for the log
+    lines: Vec<String>,
+}
+
+impl PostRunFloat {
+    pub fn new(
+        names: String,
+        exit_code: u32,
+        duration: Duration,
+        timed_out: bool,
+        lines: Vec<String>,
+    ) -> Self {
+        Self {
+            names,
+            exit_code,
+            duration,
+            timed_out,
+            lines,
+        }
+    }
+
+    /// The whole output, to open it in the log viewer
+    pub fn lines(&self) -> &[String] {
+        &self.lines
+    }
+
+    fn tail(&self) -> &[String] {
+        &self.lines[self.lines.len().saturating_sub(TAIL_LINES)..]
+    }
+}
+
+impl FloatContent for PostRunFloat {
+    fn draw(&mut self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        let block = theme
+            .block()
+            .title(format!(" {} ", self.names))
+            .title_alignment(Alignment::Center)
+            .title_style(Style::default().fg(theme.title).bold())
+            .title_bottom(Line::from(" [Enter] to close, [l] for the whole log ").centered());
+
+        let (status, color) = if self.timed_out {
+            ("timed out".to_string(), theme.fail_color)
+        } else if self.exit_code == 0 {
+            ("exited with 0".to_string(), theme.success_color)
+        } else {
+            (format!("exited with {}", self.exit_code), theme.fail_color)
+        };
+        let mut lines = vec![
+            Line::from(vec![
+                Span::styled(status, Style::default().fg(color).bold()),
+                Span::raw(format!(" after {:.1}s", self.duration.as_secs_f64())),
+            ]),
+            Line::default(),
+        ];
+        lines.extend(
+            self.tail()
+                .iter()
+                .map(|line| Line::from(line.as_str()).dim()),
+        );
+        frame.render_widget(Clear, area);
+        frame.render_widget(Paragraph::new(lines).block(block), area);
+    }
+
+    fn handle_key_event(&mut self, key: &KeyEvent, keys: &KeyBindings) -> FloatEvent {
+        match key.code {
+            KeyCode::Enter | KeyCode::Esc => FloatEvent::CloseFloat,
+            code if keys.close.contains(&code) => FloatEvent::CloseFloat,
+            KeyCode::Char('l' | 'L') => FloatEvent::ShowLog,
+            _ => FloatEvent::None,
+        }
+    }
+
+    fn is_finished(&self) -> bool {
+        true
+    }
+
+    fn get_shortcut_list(&self, _keys: &KeyBindings) -> (&str, Box<[Shortcut]>) {
+        (
+            "Finished command",
+            Box::new([
+                Shortcut::new("Enter", "Back to the list"),
+                Shortcut::new("l", "Show the whole output"),
+            ]),
+        )
+    }
+
+    /// The status, an empty line and the tail, plus the borders
+    fn preferred_size(&self) -> (u16, u16) {
+        (80, self.tail().len() as u16 + 4)
+    }
+}
+
+#[test]
+fn test_post_run_tail() {
+    let lines: Vec<String> = (0..25).map(|line| line.to_string()).collect();
+    let mut post_run = PostRunFloat::new(
+        "Full System Update".to_string(),
+        1,
+        Duration::from_secs(3),
+        false,
+        lines,
+    );
+    assert_eq!(post_run.tail().len(), TAIL_LINES);
+    assert_eq!(post_run.tail()[0], "15");
+    assert_eq!(post_run.lines().len(), 25);
+
+    let keys = KeyBindings::default();
+    assert_eq!(
+        post_run.handle_key_event(&KeyEvent::from(KeyCode::Char('L')), &keys),
+        FloatEvent::ShowLog
+    );
+    assert_eq!(
+        post_run.handle_key_event(&KeyEvent::from(KeyCode::Enter), &keys),
+        FloatEvent::CloseFloat
+    );
+}
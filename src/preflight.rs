@@ -0,0 +1,214 @@
+//! Checks that the commands the scripts need are there, before they run, instead of having them
+//! fail halfway through
+
+use std::{env, ffi::OsStr};
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::{Alignment, Rect},
+    style::{Style, Stylize},
+    text::Line,
+    widgets::{Clear, Paragraph},
+    Frame,
+};
+use tui::{
+    packagemanagers::{self, PackageManager},
+    systeminfo::System,
+};
+
+use crate::{
+    config::{describe, KeyBindings},
+    float::{FloatContent, FloatEvent},
+    hint::Shortcut,
+    list::{running_as_root, ListNode},
+    theme::Theme,
+};
+
+/// Commands that come in a package named something else. A package manager of `None` stands for
+/// all of them, so the more specific entries come first
+const PACKAGE_NAMES: [(&str, Option<PackageManager>, &str); 6] = [
+    ("fd", Some(PackageManager::Apt), "fd-find"),
+    ("fd", Some(PackageManager::Dnf), "fd-find"),
+    ("rg", None, "ripgrep"),
+    ("fc-cache", None, "fontconfig"),
+    ("pip3", Some(PackageManager::Pacman), "python-pip"),
+    ("pip3", None, "python3-pip"),
+];
+
+/// The packages `pm` installs the commands of `requires` that aren't on `PATH` with, each once
+pub fn check_requirements(requires: &[&str], pm: &PackageManager) -> Vec<String> {
+    let mut packages = Vec::new();
+    for command in missing_commands(requires) {
+        let package = package_name(command, pm).to_string();
+        if !packages.contains(&package) {
+            packages.push(package);
+        }
+    }
+    packages
+}
+
+/// The commands of `requires` that aren't on `PATH`
+pub fn missing_commands<'a>(requires: &[&'a str]) -> Vec<&'a str> {
+    let path = env::var_os("PATH").unwrap_or_default();
+    missing_in(requires, &path)
+}
+
+fn missing_in<'a>(requires: &[&'a str], path: &OsStr) -> Vec<&'a str> {
+    requires
+        .iter()
+        .copied()
+        .filter(|command| !packagemanagers::is_in_path(command, path))
+        .collect()
+}
+
+fn package_name<'a>(command: &'a str, pm: &PackageManager) -> &'a str {
+    PACKAGE_NAMES
+        .iter()
+        .find(|&&(name, manager, _)| {
+            name == command && manager.is_none_or(|manager| manager == *pm)
+        })
+        .map_or(command, |&(_, _, package)| package)
+}
+
+/// Lists what the picked commands need but is missing, and offers to install it
+pub struct MissingDepsFloat {
+    /// Packages, if there's a package manager to install them with. Otherwise the commands
+    missing: Vec<String>,
+    package_manager: Option<PackageManager>,
+    is_root: bool,
+}
+
+impl MissingDepsFloat {
+    /// `None` if nothing the nodes need is missing. `system` is `None` when it couldn't be
+    /// detected, then there's nothing to install with
+    pub fn check(nodes: &[ListNode], system: Option<&System>) -> Option<Self> {
+        let mut requires: Vec<&str> = Vec::new();
+        for &command in nodes.iter().flat_map(ListNode::requires) {
+            if !requires.contains(&command) {
+                requires.push(command);
+            }
+        }
+        let package_manager = system.and_then(System::package_manager);
+        let missing = match &package_manager {
+            Some(package_manager) => check_requirements(&requires, package_manager),
+            None => missing_commands(&requires)
+                .into_iter()
+                .map(str::to_string)
+                .collect(),
+        };
+        (!missing.is_empty()).then(|| Self {
+            missing,
+            package_manager,
+            is_root: running_as_root(),
+        })
+    }
+
+    pub fn missing(&self) -> &[String] {
+        &self.missing
+    }
+
+    /// What installs the missing packages, with sudo unless linutil runs as root. `None` without
+    /// a package manager
+    pub fn install_command(&self) -> Option<String> {
+        let package_manager = self.package_manager?;
+        let mut command = vec![package_manager.binary_name()];
+        command.extend(package_manager.install_args());
+        command.extend(self.missing.iter().map(String::as_str));
+        let command = command.join(" ");
+        Some(if self.is_root {
+            command
+        } else {
+            format!("sudo {command}")
+        })
+    }
+}
+
+impl FloatContent for MissingDepsFloat {
+    fn draw(&mut self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        let hint = match self.package_manager {
+            Some(package_manager) => {
+                format!(" [y] to install with {package_manager}, [n] to abort ")
+            }
+            None => " [n] to close ".to_string(),
+        };
+        let block = theme
+            .block()
+            .title(" Missing commands ")
+            .title_alignment(Alignment::Center)
+            .title_style(Style::default().fg(theme.title).bold())
+            .title_bottom(Line::from(hint).centered());
+
+        let mut lines = vec![Line::from(match self.package_manager {
+            Some(_) => "The scripts need these packages, that aren't installed:",
+            None => "The scripts need these commands, that aren't on PATH:",
+        })];
+        lines.extend(
+            self.missing
+                .iter()
+                .map(|missing| Line::styled(format!("  {missing}"), Style::default().bold())),
+        );
+        frame.render_widget(Clear, area);
+        frame.render_widget(Paragraph::new(lines).block(block), area);
+    }
+
+    fn handle_key_event(&mut self, key: &KeyEvent, keys: &KeyBindings) -> FloatEvent {
+        match key.code {
+            code if keys.confirm.contains(&code) && self.package_manager.is_some() => {
+                FloatEvent::ConfirmSelection
+            }
+            code if keys.abort.contains(&code) || keys.close.contains(&code) => {
+                FloatEvent::AbortConfirmation
+            }
+            KeyCode::Esc => FloatEvent::AbortConfirmation,
+            _ => FloatEvent::None,
+        }
+    }
+
+    fn is_finished(&self) -> bool {
+        false
+    }
+
+    fn get_shortcut_list(&self, keys: &KeyBindings) -> (&str, Box<[Shortcut]>) {
+        let abort = Shortcut::new(describe(&keys.abort), "Don't run the commands");
+        let shortcuts = match self.package_manager {
+            Some(_) => vec![
+                Shortcut::new(describe(&keys.confirm), "Install the missing packages"),
+                abort,
+            ],
+            None => vec![abort],
+        };
+        ("Missing commands", shortcuts.into_boxed_slice())
+    }
+
+    fn preferred_size(&self) -> (u16, u16) {
+        let height = self.missing.len() + 3;
+        (60, height.try_into().unwrap_or(u16::MAX))
+    }
+}
+
+#[test]
+fn test_check_requirements() {
+    use std::{fs, os::unix::fs::PermissionsExt};
+
+    use crate::test_dir::TestDir;
+
+    let dir = TestDir::new("preflight");
+    let git = dir.join("git");
+    fs::write(&git, "").unwrap();
+    fs::set_permissions(&git, fs::Permissions::from_mode(0o755)).unwrap();
+
+    let requires = ["git", "rg", "fd"];
+    assert_eq!(missing_in(&requires, dir.as_os_str()), ["rg", "fd"]);
+    assert_eq!(package_name("fd", &PackageManager::Apt), "fd-find");
+    assert_eq!(package_name("fd", &PackageManager::Pacman), "fd");
+    assert_eq!(package_name("pip3", &PackageManager::Apt), "python3-pip");
+    assert_eq!(package_name("rg", &PackageManager::Pacman), "ripgrep");
+
+    let float = MissingDepsFloat {
+        missing: vec!["ripgrep".to_string(), "fd".to_string()],
+        package_manager: Some(PackageManager::Pacman),
+        is_root: false,
+    };
+    assert!(float.install_command().unwrap().starts_with("sudo pacman"));
+    assert!(float.install_command().unwrap().ends_with(" ripgrep fd"));
+}
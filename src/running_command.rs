@@ -1,19 +1,21 @@
 use std::{
     io::Write,
-    sync::{Arc, Mutex},
+    sync::{
+        atomic::{AtomicBool, AtomicU32, Ordering},
+        Arc, Mutex,
+    },
     thread::JoinHandle,
+    time::{Duration, Instant},
 };
 
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
-use oneshot::{channel, Receiver};
 use portable_pty::{
     ChildKiller, CommandBuilder, ExitStatus, MasterPty, NativePtySystem, PtySize, PtySystem,
 };
 use ratatui::{
-    layout::Size,
-    style::{Color, Style, Stylize},
+    layout::{Rect, Size},
+    style::{Style, Stylize},
     text::{Line, Span},
-    widgets::{Block, Borders},
     Frame,
 };
 use tui_term::{
@@ -21,7 +23,76 @@ use tui_term::{
     widget::PseudoTerminal,
 };
 
-use crate::{float::floating_window, theme::get_theme};
+use tui::{packagemanagers::langtools, systeminfo::System};
+
+use crate::{
+    config::KeyBindings,
+    float::{FloatContent, FloatEvent},
+    hint::Shortcut,
+    metadata,
+    theme::Theme,
+};
+
+/// How commands are run
+#[derive(Debug, Clone, Default)]
+pub struct ExecutionConfig {
+    /// Commands that run for longer get killed. `None` lets them run for as long as they want
+    pub timeout: Option<Duration>,
+    /// Set for every command, on top of what linutil itself was started with
+    pub env: Vec<(String, String)>,
+    /// How many more times a command that fails is run, from `@retry`
+    pub retries: u32,
+}
+
+/// How long commands run together may take: the longest `@timeout` of theirs, or the configured
+/// timeout if none of them has one
+pub fn script_timeout(
+    timeouts: impl IntoIterator<Item = Option<Duration>>,
+    configured: Option<Duration>,
+) -> Option<Duration> {
+    timeouts.into_iter().flatten().max().or(configured)
+}
+
+/// How long to wait before running a failed command the `attempt`th time: a second, then twice
+/// as long every time after that, up to half a minute
+pub fn retry_delay(attempt: u32) -> Duration {
+    Duration::from_secs(1 << attempt.saturating_sub(2).min(5)).min(Duration::from_secs(30))
+}
+
+/// The `metadata::SCRIPT_ENV` variables, for what was detected about `system`. Scripts can use
+/// them instead of reading os-release again
+pub fn script_env(system: &System) -> Vec<(String, String)> {
+    let package_manager = system.package_manager();
+    let mut language_managers: Vec<_> = langtools::detect_language_managers().into_iter().collect();
+    language_managers.sort_unstable();
+    metadata::SCRIPT_ENV
+        .iter()
+        .filter_map(|&(name, _)| {
+            let value = match name {
+                "LINUTIL_DISTRO" => Some(system.id().to_string()).filter(|id| !id.is_empty()),
+                "LINUTIL_PM" => package_manager.map(|pm| pm.binary_name().to_string()),
+                "LINUTIL_ARCH" => system.architecture().map(str::to_string),
+                "LINUTIL_PM_INSTALL_ARGS" => package_manager.map(|pm| pm.install_args().join(" ")),
+                _ => None,
+            }?;
+            Some((name.to_string(), value))
+        })
+        .chain(language_managers.into_iter().map(|(name, found)| {
+            (
+                langtools::env_name(name),
+                if found { "1" } else { "0" }.to_string(),
+            )
+        }))
+        .collect()
+}
+
+/// What the command thread ends with
+struct Finished {
+    status: ExitStatus,
+    duration: Duration,
+    /// Whether the command was killed because it ran out of time
+    timed_out: bool,
+}
 
 /// This is a struct for storing everything connected to a running command
 // Create a new instance on every new command you want to run
@@ -30,10 +101,17 @@ pub struct RunningCommand {
     buffer: Arc<Mutex<Vec<u8>>>,
 
     /// A handle of the tread where the command is being executed
-    command_thread: Option<JoinHandle<ExitStatus>>,
+    command_thread: Option<JoinHandle<Finished>>,
 
-    /// A handle to kill the running process, it's an option because it can only be used once
-    child_killer: Option<Receiver<Box<dyn ChildKiller + Send + Sync>>>,
+    /// A handle to kill the running process. A new one for every attempt, `None` until the first
+    /// one started
+    child_killer: Arc<Mutex<Option<Box<dyn ChildKiller + Send + Sync>>>>,
+    /// Set once the command was killed, so it isn't tried again
+    killed: Arc<AtomicBool>,
+    /// Which attempt is running, counting from 1
+    attempt: Arc<AtomicU32>,
+    /// How many attempts there are at most
+    max_attempts: u32,
 
     /// A join handle for the thread that is reading all the command output and sending it to the
     /// main thread
@@ -47,14 +125,20 @@ pub struct RunningCommand {
 
     /// Only set after the process has ended
     status: Option<ExitStatus>,
+    duration: Duration,
+    timed_out: bool,
 }
 
 impl RunningCommand {
-    pub fn new(command: &str) -> Self {
+    /// Runs the commands one after another, in a single shell
+    pub fn new(commands: &[&str], config: &ExecutionConfig) -> Self {
         let pty_system = NativePtySystem::default();
         let mut cmd = CommandBuilder::new("sh");
         cmd.arg("-c");
-        cmd.arg(command);
+        cmd.arg(commands.join("\n"));
+        for (name, value) in &config.env {
+            cmd.env(name, value);
+        }
 
         let cwd = std::env::current_dir().unwrap();
         cmd.cwd(cwd);
@@ -68,20 +152,81 @@ impl RunningCommand {
             })
             .unwrap();
 
-        let (tx, rx) = channel();
-        // Thread waiting for the child to complete
-        let command_handle = std::thread::spawn(move || {
-            let mut child = pair.slave.spawn_command(cmd).unwrap();
-            let killer = child.clone_killer();
-            tx.send(killer).unwrap();
-            child.wait().unwrap()
-        });
-
-        let mut reader = pair.master.try_clone_reader().unwrap(); // This is a reader, this is where we
-
         // A buffer, shared between the thread that reads the command output, and the main tread.
         // The main thread only reads the contents
         let command_buffer: Arc<Mutex<Vec<u8>>> = Arc::new(Mutex::new(Vec::new()));
+        let child_killer: Arc<Mutex<Option<Box<dyn ChildKiller + Send + Sync>>>> =
+            Arc::new(Mutex::new(None));
+        let killed = Arc::new(AtomicBool::new(false));
+        let attempt = Arc::new(AtomicU32::new(1));
+        let timeout = config.timeout;
+        let retries = config.retries;
+        // Thread waiting for the child to complete, and starting it again if it failed
+        let command_handle = {
+            let buffer = command_buffer.clone();
+            let child_killer = child_killer.clone();
+            let killed = killed.clone();
+            let attempt = attempt.clone();
+            std::thread::spawn(move || {
+                // The timeout is for all of the attempts together
+                let started = Instant::now();
+                loop {
+                    let mut child = pair.slave.spawn_command(cmd.clone()).unwrap();
+                    *child_killer.lock().unwrap() = Some(child.clone_killer());
+                    if killed.load(Ordering::Relaxed) {
+                        let _ = child.kill();
+                    }
+                    let mut timed_out = false;
+                    let status = match timeout {
+                        None => child.wait().unwrap(),
+                        // Check on the child every now and then, until it's done or out of time
+                        Some(timeout) => loop {
+                            if let Some(status) = child.try_wait().unwrap() {
+                                break status;
+                            }
+                            if started.elapsed() >= timeout {
+                                timed_out = true;
+                                // It may already be gone, then there's nothing to kill
+                                let _ = child.kill();
+                                break child.wait().unwrap();
+                            }
+                            std::thread::sleep(Duration::from_millis(50));
+                        },
+                    };
+
+                    // Killed commands would only be killed again
+                    let current = attempt.load(Ordering::Relaxed);
+                    if status.success()
+                        || timed_out
+                        || killed.load(Ordering::Relaxed)
+                        || current > retries
+                    {
+                        break Finished {
+                            status,
+                            duration: started.elapsed(),
+                            timed_out,
+                        };
+                    }
+                    let delay = retry_delay(current + 1);
+                    let message = format!(
+                        "\r\n[linutil] Exited with {}, trying again in {}s ({} of {})\r\n",
+                        status.exit_code(),
+                        delay.as_secs(),
+                        current + 1,
+                        retries + 1
+                    );
+                    buffer.lock().unwrap().extend_from_slice(message.as_bytes());
+                    let waiting = Instant::now();
+                    while waiting.elapsed() < delay && !killed.load(Ordering::Relaxed) {
+                        std::thread::sleep(Duration::from_millis(50));
+                    }
+                    attempt.store(current + 1, Ordering::Relaxed);
+                }
+            })
+        };
+
+        let mut reader = pair.master.try_clone_reader().unwrap(); // This is a reader, this is where we
+
         let reader_handle = {
             // Arc is just a reference, so we can create an owned copy without any problem
             let command_buffer = command_buffer.clone();
@@ -107,11 +252,16 @@ impl RunningCommand {
         Self {
             buffer: command_buffer,
             command_thread: Some(command_handle),
-            child_killer: Some(rx),
+            child_killer,
+            killed,
+            attempt,
+            max_attempts: retries + 1,
             _reader_thread: reader_handle,
             pty_master: pair.master,
             writer,
             status: None,
+            duration: Duration::ZERO,
+            timed_out: false,
         }
     }
     fn screen(&mut self, size: Size) -> Screen {
@@ -134,100 +284,89 @@ impl RunningCommand {
         parser.process(buffer);
         parser.screen().clone()
     }
-    pub fn is_finished(&mut self) -> bool {
-        if let Some(command_thread) = &self.command_thread {
-            command_thread.is_finished()
+    /// The output of the command so far, as plain lines without any escape codes. While the
+    /// command runs, the last line may not be complete yet, so it's left out
+    pub fn output_lines(&self) -> Vec<String> {
+        // A screen tall enough to hold all of the output, so nothing scrolls off of it
+        const WIDTH: u16 = 500;
+        let mutex = self.buffer.lock();
+        let buffer = mutex.as_ref().unwrap();
+        let line_count = buffer.iter().filter(|&&byte| byte == b'\n').count() + 1;
+        let mut parser = vt100::Parser::new(line_count.try_into().unwrap_or(u16::MAX), WIDTH, 0);
+        parser.process(buffer);
+
+        let mut lines: Vec<String> = parser
+            .screen()
+            .rows(0, WIDTH)
+            .map(|row| row.trim_end().to_string())
+            .collect();
+        if self.is_finished() {
+            while lines.last().is_some_and(String::is_empty) {
+                lines.pop();
+            }
         } else {
-            true
+            lines.truncate(line_count - 1);
         }
+        lines
     }
+
     /// This function will block if the command is not finished
     fn get_exit_status(&mut self) -> ExitStatus {
         if self.command_thread.is_some() {
             let handle = self.command_thread.take().unwrap();
-            let exit_status = handle.join().unwrap();
-            self.status = Some(exit_status.clone());
-            exit_status
+            let finished = handle.join().unwrap();
+            self.status = Some(finished.status.clone());
+            self.duration = finished.duration;
+            self.timed_out = finished.timed_out;
+            finished.status
         } else {
             self.status.as_ref().unwrap().clone()
         }
     }
 
-    pub fn draw(&mut self, frame: &mut Frame) {
-        {
-            let theme = get_theme();
-            // Funny name
-            let floater = floating_window(frame.size());
-
-            let inner_size = Size {
-                width: floater.width - 2, // Because we add a `Block` with a border
-                height: floater.height - 2,
-            };
-
-            // When the command is running
-            let term_border = if !self.is_finished() {
-                Block::default()
-                    .borders(Borders::ALL)
-                    .title_top(Line::from("Running the command....").centered())
-                    .title_style(Style::default().reversed())
-                    .title_bottom(Line::from("Press Ctrl-C to KILL the command"))
-            } else {
-                // This portion is just for pretty colors.
-                // You can use multiple `Span`s with different styles each, to construct a line,
-                // which can be used as a list item, or in this case a `Block` title
-
-                let mut title_line = if self.get_exit_status().success() {
-                    Line::from(
-                        Span::default()
-                            .content("SUCCESS!")
-                            .style(Style::default().fg(theme.success_color).reversed()),
-                    )
-                } else {
-                    Line::from(
-                        Span::default()
-                            .content("FAILED!")
-                            .style(Style::default().fg(theme.fail_color).reversed()),
-                    )
-                };
-
-                title_line.push_span(
-                    Span::default()
-                        .content(" press <ENTER> to close this window ")
-                        .style(Style::default()),
-                );
-
-                Block::default()
-                    .borders(Borders::ALL)
-                    .title_top(title_line.centered())
-            };
-            let screen = self.screen(inner_size); // when the terminal is changing a lot, there
-                                                  // will be 1 frame of lag on resizing
-            let pseudo_term = PseudoTerminal::new(&screen).block(term_border);
-            frame.render_widget(pseudo_term, floater);
+    /// The exit code of the command and how long it ran, once it's finished
+    pub fn exit(&mut self) -> Option<(u32, Duration)> {
+        if !self.is_finished() {
+            return None;
         }
+        let code = self.get_exit_status().exit_code();
+        Some((code, self.duration))
     }
-    /// Send SIGHUB signal, *not* SIGKILL or SIGTERM, to the child process
-    pub fn kill_child(&mut self) {
-        if !self.is_finished() {
-            let mut killer = self.child_killer.take().unwrap().recv().unwrap();
-            killer.kill().unwrap();
+
+    /// Whether the command was killed for running longer than the timeout. Only known once it's
+    /// finished
+    pub fn timed_out(&mut self) -> bool {
+        self.is_finished() && {
+            self.get_exit_status();
+            self.timed_out
         }
     }
 
-    /// Handle key events of the running command "window". Returns true when the "window" should be
-    /// closed
-    pub fn handle_key_event(&mut self, key: &KeyEvent) -> bool {
-        match key.code {
-            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                self.kill_child()
+    /// Which attempt is running, and how many there are at most. Both are 1 for commands that
+    /// aren't tried again
+    pub fn attempt(&self) -> (u32, u32) {
+        (self.attempt.load(Ordering::Relaxed), self.max_attempts)
+    }
+
+    fn running_title(&self) -> String {
+        match self.attempt() {
+            (_, 1) => "Running the command....".to_string(),
+            (attempt, max_attempts) => {
+                format!("Running the command.... (attempt {attempt} of {max_attempts})")
             }
+        }
+    }
 
-            KeyCode::Enter if self.is_finished() => {
-                return true;
+    /// Send SIGHUB signal, *not* SIGKILL or SIGTERM, to the child process. It isn't tried again
+    /// after that
+    pub fn kill_child(&mut self) {
+        if !self.is_finished() {
+            self.killed.store(true, Ordering::Relaxed);
+            if let Some(killer) = self.child_killer.lock().unwrap().as_mut() {
+                // It may have exited in the meantime
+                let _ = killer.kill();
             }
-            _ => self.handle_passthrough_key_event(key),
-        };
-        false
+        }
     }
 
     /// Convert the KeyEvent to pty key codes, and send them to the virtual terminal
@@ -280,3 +419,194 @@ impl RunningCommand {
         let _ = self.writer.write_all(&input_bytes);
     }
 }
+
+impl FloatContent for RunningCommand {
+    fn draw(&mut self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        {
+            let inner_size = Size {
+                width: area.width - 2, // Because we add a `Block` with a border
+                height: area.height - 2,
+            };
+
+            // When the command is running
+            let term_border = if !self.is_finished() {
+                theme
+                    .block()
+                    .title_top(Line::from(self.running_title()).centered())
+                    .title_style(Style::default().reversed())
+                    .title_bottom(Line::from(
+                        "Press Ctrl-C to KILL the command, Ctrl-O to see all of the output",
+                    ))
+            } else {
+                // This portion is just for pretty colors.
+                // You can use multiple `Span`s with different styles each, to construct a line,
+                // which can be used as a list item, or in this case a `Block` title
+
+                let mut title_line = if self.timed_out() {
+                    Line::from(
+                        Span::default()
+                            .content("TIMED OUT!")
+                            .style(Style::default().fg(theme.fail_color).reversed()),
+                    )
+                } else if self.get_exit_status().success() {
+                    Line::from(
+                        Span::default()
+                            .content("SUCCESS!")
+                            .style(Style::default().fg(theme.success_color).reversed()),
+                    )
+                } else {
+                    Line::from(
+                        Span::default()
+                            .content("FAILED!")
+                            .style(Style::default().fg(theme.fail_color).reversed()),
+                    )
+                };
+
+                title_line.push_span(
+                    Span::default()
+                        .content(" press <ENTER> to close this window ")
+                        .style(Style::default()),
+                );
+
+                theme.block().title_top(title_line.centered())
+            };
+            let screen = self.screen(inner_size); // when the terminal is changing a lot, there
+                                                  // will be 1 frame of lag on resizing
+            let pseudo_term = PseudoTerminal::new(&screen).block(term_border);
+            frame.render_widget(pseudo_term, area);
+        }
+    }
+
+    /// Handle key events of the running command "window". Returns `FloatEvent::CloseFloat` when
+    /// the "window" should be closed. These keys can't be remapped, since they need modifiers
+    fn handle_key_event(&mut self, key: &KeyEvent, _keys: &KeyBindings) -> FloatEvent {
+        match key.code {
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.kill_child()
+            }
+            KeyCode::Char('o') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                return FloatEvent::ShowLog;
+            }
+
+            KeyCode::Enter if self.is_finished() => {
+                return FloatEvent::CloseFloat;
+            }
+            _ => self.handle_passthrough_key_event(key),
+        };
+        FloatEvent::None
+    }
+
+    fn is_finished(&self) -> bool {
+        if let Some(command_thread) = &self.command_thread {
+            command_thread.is_finished()
+        } else {
+            true
+        }
+    }
+
+    fn get_shortcut_list(&self, _keys: &KeyBindings) -> (&str, Box<[Shortcut]>) {
+        (
+            "Running command",
+            Box::new([
+                Shortcut::new("Ctrl-C", "Kill the command"),
+                Shortcut::new("Ctrl-O", "Show all of the output"),
+                Shortcut::new("Enter", "Close, once the command is done"),
+            ]),
+        )
+    }
+
+    /// Commands can print a lot, so take all the space there is
+    fn preferred_size(&self) -> (u16, u16) {
+        (u16::MAX, u16::MAX)
+    }
+}
+
+#[test]
+fn test_timeout_kills_command() {
+    let config = ExecutionConfig {
+        timeout: Some(Duration::from_millis(200)),
+        ..ExecutionConfig::default()
+    };
+    let mut command = RunningCommand::new(&["sleep 10"], &config);
+    let started = Instant::now();
+    while !command.is_finished() {
+        assert!(started.elapsed() < Duration::from_secs(5), "never killed");
+        std::thread::sleep(Duration::from_millis(20));
+    }
+    assert!(command.timed_out());
+    assert!(!command.get_exit_status().success());
+
+    let mut command = RunningCommand::new(&["true"], &config);
+    while !command.is_finished() {
+        std::thread::sleep(Duration::from_millis(20));
+    }
+    assert!(!command.timed_out());
+}
+
+#[test]
+fn test_env_is_set() {
+    let config = ExecutionConfig {
+        env: vec![("LINUTIL_DISTRO".to_string(), "arch".to_string())],
+        ..ExecutionConfig::default()
+    };
+    let command = RunningCommand::new(&["echo \"distro=$LINUTIL_DISTRO\""], &config);
+    let started = Instant::now();
+    while !command
+        .output_lines()
+        .iter()
+        .any(|line| line == "distro=arch")
+    {
+        assert!(started.elapsed() < Duration::from_secs(5), "never printed");
+        std::thread::sleep(Duration::from_millis(20));
+    }
+
+    if let Ok(system) = System::info() {
+        for (name, _) in script_env(&system) {
+            let documented = match name.strip_prefix("LINUTIL_HAS_") {
+                Some(_) => "LINUTIL_HAS_<TOOL>",
+                None => &name,
+            };
+            assert!(metadata::SCRIPT_ENV
+                .iter()
+                .any(|&(name, _)| name == documented));
+        }
+    }
+}
+
+#[test]
+fn test_script_timeout_overrides_config() {
+    let configured = Some(Duration::from_secs(60));
+    let secs = |secs| Some(Duration::from_secs(secs));
+    assert_eq!(script_timeout([None], configured), configured);
+    assert_eq!(script_timeout([secs(600)], configured), secs(600));
+    assert_eq!(script_timeout([secs(10)], configured), secs(10));
+    assert_eq!(script_timeout([secs(10), None, secs(30)], None), secs(30));
+    assert_eq!(script_timeout([None, None], None), None);
+}
+
+#[test]
+fn test_failed_command_is_retried() {
+    assert_eq!(retry_delay(2), Duration::from_secs(1));
+    assert_eq!(retry_delay(3), Duration::from_secs(2));
+    assert_eq!(retry_delay(20), Duration::from_secs(30));
+
+    let config = ExecutionConfig {
+        retries: 1,
+        ..ExecutionConfig::default()
+    };
+    let mut command = RunningCommand::new(&["false"], &config);
+    let started = Instant::now();
+    while !command.is_finished() {
+        assert!(
+            started.elapsed() < Duration::from_secs(10),
+            "never finished"
+        );
+        std::thread::sleep(Duration::from_millis(20));
+    }
+    assert_eq!(command.attempt(), (2, 2));
+    assert!(!command.get_exit_status().success());
+    assert!(command
+        .output_lines()
+        .iter()
+        .any(|line| line.contains("trying again in 1s (2 of 2)")));
+}
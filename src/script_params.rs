@@ -0,0 +1,301 @@
+use std::path::{Path, PathBuf};
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::{Alignment, Rect},
+    style::{Style, Stylize},
+    text::{Line, Span},
+    widgets::{Clear, Paragraph},
+    Frame,
+};
+
+use crate::{
+    config::KeyBindings,
+    float::{FloatContent, FloatEvent},
+    hint::Shortcut,
+    list::ListNode,
+    metadata::{ParamKind, ScriptParam},
+    theme::Theme,
+};
+
+/// One input of the form
+struct Field {
+    param: &'static ScriptParam,
+    value: String,
+    /// Why the value isn't valid, after the form was last submitted
+    error: Option<&'static str>,
+}
+
+/// Asks for the `@param` values of the picked scripts, before they run. Submitting the form runs
+/// them, like confirming the prompt would
+pub struct ScriptParamFloat {
+    fields: Vec<Field>,
+    /// The field that gets the keys
+    selected: usize,
+}
+
+impl ScriptParamFloat {
+    /// `None` if none of the nodes has any params. Scripts that ask for the same name share the
+    /// field
+    pub fn new(nodes: &[ListNode]) -> Option<Self> {
+        let mut fields: Vec<Field> = Vec::new();
+        for param in nodes.iter().flat_map(ListNode::params) {
+            if fields.iter().all(|field| field.param.name != param.name) {
+                fields.push(Field {
+                    param,
+                    // Yes or no can't be left empty
+                    value: match param.kind {
+                        ParamKind::Bool => "no".to_string(),
+                        _ => String::new(),
+                    },
+                    error: None,
+                });
+            }
+        }
+        (!fields.is_empty()).then_some(Self {
+            fields,
+            selected: 0,
+        })
+    }
+
+    /// The `LINUTIL_PARAM_` variables for the values, if all of them are valid. Otherwise the
+    /// fields get their errors, and the first one that has one is selected
+    pub fn env(&mut self) -> Option<Vec<(String, String)>> {
+        let home = std::env::var_os("HOME").map(PathBuf::from);
+        let mut env = Vec::new();
+        for field in &mut self.fields {
+            match validate(field.param.kind, &field.value, home.as_deref()) {
+                Ok(value) => {
+                    field.error = None;
+                    env.push((field.param.env_name(), value));
+                }
+                Err(err) => field.error = Some(err),
+            }
+        }
+        match self.fields.iter().position(|field| field.error.is_some()) {
+            Some(invalid) => {
+                self.selected = invalid;
+                None
+            }
+            None => Some(env),
+        }
+    }
+
+    fn select_next(&mut self) {
+        self.selected = (self.selected + 1) % self.fields.len();
+    }
+
+    fn select_previous(&mut self) {
+        self.selected = self
+            .selected
+            .checked_sub(1)
+            .unwrap_or(self.fields.len() - 1);
+    }
+}
+
+/// The value the script gets, or why it can't have it
+fn validate(kind: ParamKind, value: &str, home: Option<&Path>) -> Result<String, &'static str> {
+    let value = value.trim();
+    match kind {
+        ParamKind::String if value.is_empty() => Err("This can't be empty"),
+        ParamKind::String => Ok(value.to_string()),
+        ParamKind::Int => value
+            .parse::<u64>()
+            .map(|number| number.to_string())
+            .map_err(|_| "This has to be a whole number, 0 or more"),
+        ParamKind::Path => {
+            let path = match (value.strip_prefix('~'), home) {
+                (Some(rest), Some(home)) if rest.is_empty() || rest.starts_with('/') => {
+                    home.join(rest.trim_start_matches('/'))
+                }
+                _ => PathBuf::from(value),
+            };
+            let dir = match path.parent() {
+                Some(parent) if !parent.as_os_str().is_empty() => parent,
+                _ => Path::new("."),
+            };
+            if value.is_empty() {
+                Err("This can't be empty")
+            } else if !dir.is_dir() {
+                Err("The directory this is in doesn't exist")
+            } else {
+                Ok(path.to_string_lossy().into_owned())
+            }
+        }
+        ParamKind::Bool => match value.to_ascii_lowercase().as_str() {
+            "y" | "yes" | "true" | "1" => Ok("true".to_string()),
+            "n" | "no" | "false" | "0" => Ok("false".to_string()),
+            _ => Err("This has to be yes or no"),
+        },
+    }
+}
+
+impl FloatContent for ScriptParamFloat {
+    fn draw(&mut self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        let block = theme
+            .block()
+            .title(" The scripts need a few things ")
+            .title_alignment(Alignment::Center)
+            .title_style(Style::default().fg(theme.title).bold())
+            .title_bottom(
+                Line::from(" [Tab] next field, [Enter] to run, [Esc] to abort ").centered(),
+            );
+
+        let mut lines = Vec::new();
+        for (idx, field) in self.fields.iter().enumerate() {
+            let kind = match field.param.kind {
+                ParamKind::String => "text",
+                ParamKind::Int => "number",
+                ParamKind::Path => "path",
+                ParamKind::Bool => "yes/no, [Space] toggles",
+            };
+            let label = match field.param.label {
+                "" => field.param.name,
+                label => label,
+            };
+            lines.push(Line::styled(
+                format!("{label} ({kind})"),
+                Style::default().fg(theme.title),
+            ));
+            let input = if idx == self.selected {
+                Line::from(vec![
+                    Span::raw(format!("> {}", field.value)),
+                    Span::raw(" ").reversed(),
+                ])
+                .style(Style::default().bg(theme.selection))
+            } else {
+                Line::from(format!("  {}", field.value))
+            };
+            lines.push(input);
+            lines.push(match field.error {
+                Some(err) => {
+                    Line::styled(format!("  {err}"), Style::default().fg(theme.fail_color))
+                }
+                None => Line::default(),
+            });
+        }
+        frame.render_widget(Clear, area);
+        frame.render_widget(Paragraph::new(lines).block(block), area);
+    }
+
+    fn handle_key_event(&mut self, key: &KeyEvent, _keys: &KeyBindings) -> FloatEvent {
+        match key.code {
+            KeyCode::Tab | KeyCode::Down => self.select_next(),
+            KeyCode::BackTab | KeyCode::Up => self.select_previous(),
+            KeyCode::Enter => return FloatEvent::ConfirmSelection,
+            KeyCode::Esc => return FloatEvent::AbortConfirmation,
+            code => {
+                let field = &mut self.fields[self.selected];
+                match code {
+                    KeyCode::Char(' ') if field.param.kind == ParamKind::Bool => {
+                        let yes = validate(ParamKind::Bool, &field.value, None)
+                            .is_ok_and(|value| value == "true");
+                        field.value = if yes { "no" } else { "yes" }.to_string();
+                    }
+                    KeyCode::Char(c) => field.value.push(c),
+                    KeyCode::Backspace => {
+                        field.value.pop();
+                    }
+                    _ => {}
+                }
+            }
+        }
+        FloatEvent::None
+    }
+
+    fn is_finished(&self) -> bool {
+        false
+    }
+
+    fn get_shortcut_list(&self, _keys: &KeyBindings) -> (&str, Box<[Shortcut]>) {
+        (
+            "Script parameters",
+            Box::new([
+                Shortcut::new("Tab/Down", "Next field"),
+                Shortcut::new("Shift-Tab/Up", "Previous field"),
+                Shortcut::new("Space", "Toggle a yes/no field"),
+                Shortcut::new("Enter", "Run the scripts with these values"),
+                Shortcut::new("Esc", "Don't run the scripts"),
+            ]),
+        )
+    }
+
+    fn preferred_size(&self) -> (u16, u16) {
+        let height = self.fields.len() * 3 + 2;
+        (70, height.try_into().unwrap_or(u16::MAX))
+    }
+}
+
+#[test]
+fn test_validate_params() {
+    let home = std::env::temp_dir();
+    assert_eq!(
+        validate(ParamKind::Int, " 8080 ", None),
+        Ok("8080".to_string())
+    );
+    assert!(validate(ParamKind::Int, "-1", None).is_err());
+    assert!(validate(ParamKind::String, "  ", None).is_err());
+    assert_eq!(
+        validate(ParamKind::Bool, "Yes", None),
+        Ok("true".to_string())
+    );
+    assert!(validate(ParamKind::Bool, "maybe", None).is_err());
+    assert_eq!(
+        validate(ParamKind::Path, "~/linutil", Some(&home)),
+        Ok(home.join("linutil").to_string_lossy().into_owned())
+    );
+    assert!(validate(ParamKind::Path, "/nonexistent/linutil", None).is_err());
+}
+
+#[test]
+fn test_param_form() {
+    static PARAMS: [ScriptParam; 2] = [
+        ScriptParam {
+            name: "port",
+            kind: ParamKind::Int,
+            label: "The port",
+        },
+        ScriptParam {
+            name: "enable",
+            kind: ParamKind::Bool,
+            label: "",
+        },
+    ];
+    let mut form = ScriptParamFloat {
+        fields: PARAMS
+            .iter()
+            .map(|param| Field {
+                param,
+                value: String::new(),
+                error: None,
+            })
+            .collect(),
+        selected: 0,
+    };
+    let keys = KeyBindings::default();
+    let press =
+        |form: &mut ScriptParamFloat, code| form.handle_key_event(&KeyEvent::from(code), &keys);
+
+    press(&mut form, KeyCode::Char('8'));
+    press(&mut form, KeyCode::Tab);
+    press(&mut form, KeyCode::Char(' '));
+    assert_eq!(form.fields[1].value, "yes");
+    press(&mut form, KeyCode::Tab);
+    assert_eq!(form.selected, 0);
+    press(&mut form, KeyCode::Char('x'));
+    assert_eq!(
+        press(&mut form, KeyCode::Enter),
+        FloatEvent::ConfirmSelection
+    );
+    assert!(form.env().is_none());
+    assert!(form.fields[0].error.is_some());
+
+    press(&mut form, KeyCode::Backspace);
+    assert_eq!(
+        form.env(),
+        Some(vec![
+            ("LINUTIL_PARAM_PORT".to_string(), "8".to_string()),
+            ("LINUTIL_PARAM_ENABLE".to_string(), "true".to_string()),
+        ])
+    );
+}
@@ -0,0 +1,117 @@
+use ratatui::{
+    layout::Rect,
+    style::{Style, Stylize},
+    text::{Line, Span},
+    widgets::Paragraph,
+    Frame,
+};
+
+use crate::{metadata::ScriptMeta, theme::Theme};
+
+/// Words that get the keyword color
+const SHELL_KEYWORDS: [&str; 19] = [
+    "if", "then", "else", "elif", "fi", "case", "esac", "for", "while", "until", "do", "done",
+    "in", "function", "return", "local", "export", "exit", "break",
+];
+
+/// Draw the first lines of the command, that fit in `area`. The description of the script, if
+/// it has one, goes in the title
+pub fn draw(
+    frame: &mut Frame,
+    area: Rect,
+    theme: &Theme,
+    command: &str,
+    meta: Option<&ScriptMeta>,
+) {
+    // Minus the borders
+    let height = area.height.saturating_sub(2) as usize;
+    let lines: Vec<Line> = command
+        .lines()
+        .take(height)
+        .map(|line| highlight_line(line, theme))
+        .collect();
+    let title = match meta {
+        Some(meta) if !meta.author.is_empty() => {
+            format!("{}, by {}", meta.description, meta.author)
+        }
+        Some(meta) if !meta.description.is_empty() => meta.description.to_string(),
+        _ => "Script preview".to_string(),
+    };
+    let preview = Paragraph::new(lines).block(theme.block().title(title));
+    frame.render_widget(preview, area);
+}
+
+/// Color the keywords, strings, variables and comments of a line of shell
+pub fn highlight_line(line: &str, theme: &Theme) -> Line<'static> {
+    let keyword = Style::default().fg(theme.title).bold();
+    let string = Style::default().fg(theme.cmd_color);
+    let variable = Style::default().fg(theme.dir_color);
+    let comment = Style::default().fg(theme.border).italic();
+
+    let mut spans = Vec::new();
+    let mut rest = line;
+    while let Some(ch) = rest.chars().next() {
+        let (len, style) = match ch {
+            '#' if is_word_start(line, rest) => (rest.len(), Some(comment)),
+            '"' | '\'' => {
+                // Up to the closing quote, or the end of the line if there isn't one
+                let end = rest[1..].find(ch).map_or(rest.len(), |idx| idx + 2);
+                (end, Some(string))
+            }
+            '$' => {
+                let end = rest[1..]
+                    .find(|ch: char| !(ch.is_alphanumeric() || "_{}".contains(ch)))
+                    .map_or(rest.len(), |idx| idx + 1);
+                (end, Some(variable))
+            }
+            ch if is_word_char(ch) => {
+                let end = rest.find(|ch| !is_word_char(ch)).unwrap_or(rest.len());
+                let word = &rest[..end];
+                (end, SHELL_KEYWORDS.contains(&word).then_some(keyword))
+            }
+            ch => (ch.len_utf8(), None),
+        };
+        let (text, tail) = rest.split_at(len);
+        spans.push(match style {
+            Some(style) => Span::styled(text.to_string(), style),
+            None => Span::raw(text.to_string()),
+        });
+        rest = tail;
+    }
+    Line::from(spans)
+}
+
+fn is_word_char(ch: char) -> bool {
+    ch.is_alphanumeric() || ch == '_' || ch == '-'
+}
+
+/// Whether `rest`, the end of `line`, starts a new word. A `#` in the middle of a word isn't a
+/// comment
+fn is_word_start(line: &str, rest: &str) -> bool {
+    let before = &line[..line.len() - rest.len()];
+    before.chars().last().is_none_or(char::is_whitespace)
+}
+
+#[test]
+fn test_highlight_line() {
+    let theme = Theme::dark();
+    let keyword = Style::default().fg(theme.title).bold();
+    let line = highlight_line(r#"if [ "$x" = a#b ]; then # done"#, &theme);
+
+    let spans: Vec<(&str, Style)> = line
+        .spans
+        .iter()
+        .map(|span| (span.content.as_ref(), span.style))
+        .collect();
+    assert_eq!(spans[0], ("if", keyword));
+    assert!(spans.contains(&("\"$x\"", Style::default().fg(theme.cmd_color))));
+    assert!(spans.contains(&("then", keyword)));
+    // Not a comment, it's in the middle of a word
+    assert!(spans.contains(&("#", Style::default())));
+    assert_eq!(
+        spans.last().unwrap(),
+        &("# done", Style::default().fg(theme.border).italic())
+    );
+    // Nothing gets lost
+    assert_eq!(line.to_string(), r#"if [ "$x" = a#b ]; then # done"#);
+}
@@ -0,0 +1,44 @@
+use std::borrow::Cow;
+
+use ratatui::widgets::Paragraph;
+
+/// What the spinner shows, one after the other
+const FRAMES: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+
+/// Shows that something is going on in the background, like detecting the system
+pub struct Spinner {
+    /// The index in `FRAMES`
+    frame: u8,
+    message: Cow<'static, str>,
+}
+
+impl Spinner {
+    pub fn new(message: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            frame: 0,
+            message: message.into(),
+        }
+    }
+
+    /// Moves on to the next frame, it's called every time the TUI is drawn
+    pub fn tick(&mut self) {
+        self.frame = (self.frame + 1) % FRAMES.len() as u8;
+    }
+
+    /// The spinner, followed by the message, styled and placed by whatever it's drawn in
+    pub fn paragraph(&self) -> Paragraph<'_> {
+        Paragraph::new(format!("{} {} ", FRAMES[self.frame as usize], self.message))
+    }
+}
+
+#[test]
+fn test_spinner_cycles() {
+    let mut spinner = Spinner::new("Detecting the system");
+    assert_eq!(spinner.frame, 0);
+    for _ in 0..FRAMES.len() - 1 {
+        spinner.tick();
+    }
+    assert_eq!(FRAMES[spinner.frame as usize], '⠏');
+    spinner.tick();
+    assert_eq!(spinner.frame, 0);
+}
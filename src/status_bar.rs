@@ -0,0 +1,70 @@
+use std::borrow::Cow;
+
+use ratatui::{
+    layout::{Alignment, Rect},
+    style::Stylize,
+    widgets::Paragraph,
+    Frame,
+};
+use tui::systeminfo::System;
+
+use crate::{spinner::Spinner, theme::Theme};
+
+/// A single line at the bottom of the screen, with what's going on at the moment on the left, and
+/// what system we're running on on the right
+pub struct StatusBar<'a> {
+    message: Cow<'a, str>,
+    /// Shown instead of the system, while it's being detected
+    spinner: Option<&'a Spinner>,
+}
+
+impl<'a> StatusBar<'a> {
+    pub fn new(message: impl Into<Cow<'a, str>>) -> Self {
+        Self {
+            message: message.into(),
+            spinner: None,
+        }
+    }
+
+    pub fn detecting(mut self, spinner: Option<&'a Spinner>) -> Self {
+        self.spinner = spinner;
+        self
+    }
+
+    /// `system` is `None` when it couldn't be detected
+    pub fn draw(&self, frame: &mut Frame, area: Rect, theme: &Theme, system: Option<&System>) {
+        let style = theme.base_style().reversed();
+        frame.render_widget(
+            Paragraph::new(format!(" {}", self.message)).style(style),
+            area,
+        );
+
+        if let Some(spinner) = self.spinner {
+            frame.render_widget(
+                spinner.paragraph().style(style).alignment(Alignment::Right),
+                area,
+            );
+            return;
+        }
+        let system_info = match system {
+            Some(system) => {
+                let package_manager = match system.package_manager() {
+                    // Running it now would fail, so scripts that need it would too
+                    Some(package_manager) if system.package_manager_locked() => {
+                        format!("{package_manager} [locked]")
+                    }
+                    Some(package_manager) => package_manager.to_string(),
+                    None => "no package manager".to_string(),
+                };
+                format!("{package_manager} | {} ", system.pretty_name())
+            }
+            None => "unknown system ".to_string(),
+        };
+        frame.render_widget(
+            Paragraph::new(system_info)
+                .style(style)
+                .alignment(Alignment::Right),
+            area,
+        );
+    }
+}
@@ -0,0 +1,94 @@
+//! Temperatures from the hardware monitoring drivers. They change all the time, so unlike the rest
+//! of `systeminfo` they're read again on every call, and aren't part of `System`
+
+use std::{fs, path::Path};
+
+/// The temperatures of `/sys/class/hwmon`, in degrees Celsius, with the label of each sensor.
+/// The CPU ones come from `coretemp` or `k10temp`, the other chips that have sensors, like NVMe
+/// drives, are in there too. Empty if there are no sensors, like in most virtual machines
+pub fn get_cpu_temperatures() -> Vec<(Box<str>, f32)> {
+    temperatures_in(Path::new("/sys/class/hwmon"))
+}
+
+/// Every chip is a `hwmon<N>` directory with the driver in `name`, like `coretemp` or `k10temp`,
+/// and a `temp<N>_input` in millidegrees for each sensor. Only some sensors have a `temp<N>_label`,
+/// the others are named after their chip and number
+fn temperatures_in(hwmon_class: &Path) -> Vec<(Box<str>, f32)> {
+    let Ok(entries) = fs::read_dir(hwmon_class) else {
+        return Vec::new();
+    };
+    let mut chips: Vec<_> = entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let name = entry.file_name();
+            let number = name.to_str()?.strip_prefix("hwmon")?.parse::<u32>().ok()?;
+            Some((number, entry.path()))
+        })
+        .collect();
+    chips.sort_by_key(|&(number, _)| number);
+
+    let mut temperatures = Vec::new();
+    for (_, chip) in chips {
+        let Ok(files) = fs::read_dir(&chip) else {
+            continue;
+        };
+        let mut sensors: Vec<u32> = files
+            .filter_map(Result::ok)
+            .filter_map(|file| {
+                let name = file.file_name();
+                let name = name.to_str()?;
+                name.strip_prefix("temp")?
+                    .strip_suffix("_input")?
+                    .parse()
+                    .ok()
+            })
+            .collect();
+        sensors.sort_unstable();
+
+        let read = |file: String| fs::read_to_string(chip.join(file)).ok();
+        let chip_name = read("name".to_string());
+        let chip_name = chip_name.as_deref().map_or("hwmon", str::trim);
+        for sensor in sensors {
+            // Sensors that aren't connected can't be read, or say something nonsensical
+            let Some(millidegrees) = read(format!("temp{sensor}_input"))
+                .and_then(|input| input.trim().parse::<i64>().ok())
+            else {
+                continue;
+            };
+            let label = read(format!("temp{sensor}_label"))
+                .map(|label| label.trim().to_string())
+                .filter(|label| !label.is_empty())
+                .unwrap_or_else(|| format!("{chip_name} temp{sensor}"));
+            temperatures.push((label.into(), millidegrees as f32 / 1000.0));
+        }
+    }
+    temperatures
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_dir::TestDir;
+
+    #[test]
+    fn temperatures_are_read_and_labelled() {
+        let hwmon_class = TestDir::new("hwmon");
+        assert!(temperatures_in(&hwmon_class).is_empty());
+
+        let coretemp = hwmon_class.join("hwmon2");
+        let acpi = hwmon_class.join("hwmon0");
+        fs::create_dir_all(&coretemp).unwrap();
+        fs::create_dir_all(&acpi).unwrap();
+        fs::write(coretemp.join("name"), "coretemp\n").unwrap();
+        fs::write(coretemp.join("temp1_input"), "45500\n").unwrap();
+        fs::write(coretemp.join("temp1_label"), "Package id 0\n").unwrap();
+        fs::write(coretemp.join("temp2_input"), "garbage\n").unwrap();
+        fs::write(acpi.join("name"), "acpitz\n").unwrap();
+        fs::write(acpi.join("temp1_input"), "27800\n").unwrap();
+
+        assert_eq!(
+            temperatures_in(&hwmon_class),
+            vec![("acpitz temp1".into(), 27.8), ("Package id 0".into(), 45.5)]
+        );
+    }
+}
@@ -0,0 +1,2501 @@
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    env, fmt, fs,
+    path::{Path, PathBuf},
+    process::Command,
+    sync::OnceLock,
+};
+
+use crate::packagemanagers::{self, PackageManager};
+
+pub mod hwmon;
+mod snapshot;
+mod watch;
+
+pub use snapshot::{FileChange, SystemDiff, SystemSnapshot};
+
+/// Everything we know about the system we are running on. Only os-release is read up front, the
+/// rest is detected the first time it's asked for, so what's never asked for costs nothing
+#[derive(Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "Detected", from = "Detected")
+)]
+pub struct System {
+    os_release: OsRelease,
+    package_manager: OnceLock<Option<PackageManager>>,
+    kernel_version: OnceLock<Option<Box<str>>>,
+    /// The model and the count come from the same file
+    cpu: OnceLock<(Option<Box<str>>, Option<u32>)>,
+    memory: OnceLock<MemoryInfo>,
+    architecture: OnceLock<Option<Box<str>>>,
+    init_system: OnceLock<Option<InitSystem>>,
+    display_server: OnceLock<Option<DisplayServer>>,
+    uptime_seconds: OnceLock<Option<u64>>,
+    virt_env: OnceLock<Option<VirtEnv>>,
+    hostname: OnceLock<Box<str>>,
+    current_user: OnceLock<Box<str>>,
+    is_root: OnceLock<bool>,
+    current_shell: OnceLock<Option<Box<str>>>,
+    desktop_environment: OnceLock<Option<DesktopEnvironment>>,
+    audio_server: OnceLock<Option<AudioServer>>,
+    gpu_vendor: OnceLock<Option<GpuVendor>>,
+    load_average: OnceLock<Option<(f32, f32, f32)>>,
+    machine_id: OnceLock<Option<Box<str>>>,
+    boot_mode: OnceLock<BootMode>,
+    secure_boot: OnceLock<Option<SecureBootStatus>>,
+    root_filesystem: OnceLock<Option<Box<str>>>,
+    tpm_available: OnceLock<bool>,
+    /// Whether there's a battery, and how full it is
+    battery: OnceLock<(bool, Option<u8>)>,
+    bluetooth_present: OnceLock<bool>,
+    wifi_interfaces: OnceLock<Vec<Box<str>>>,
+    locale: OnceLock<Option<Box<str>>>,
+    timezone: OnceLock<Option<Box<str>>>,
+    additional_package_managers: OnceLock<Vec<PackageManager>>,
+    package_manager_locked: OnceLock<bool>,
+}
+
+/// What `/proc/meminfo` says, in mebibytes. The file is read once for all of it
+#[derive(Debug, Clone, Copy, Default)]
+struct MemoryInfo {
+    total_mb: Option<u64>,
+    swap_total_mb: Option<u64>,
+    swap_free_mb: Option<u64>,
+}
+
+/// Every field of `System`, detected. It's what `System` is serialized as
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct Detected {
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    os_release: OsRelease,
+    package_manager: Option<PackageManager>,
+    kernel_version: Option<Box<str>>,
+    cpu_model: Option<Box<str>>,
+    cpu_count: Option<u32>,
+    total_memory_mb: Option<u64>,
+    architecture: Option<Box<str>>,
+    init_system: Option<InitSystem>,
+    display_server: Option<DisplayServer>,
+    uptime_seconds: Option<u64>,
+    virt_env: Option<VirtEnv>,
+    hostname: Box<str>,
+    current_user: Box<str>,
+    is_root: bool,
+    current_shell: Option<Box<str>>,
+    desktop_environment: Option<DesktopEnvironment>,
+    audio_server: Option<AudioServer>,
+    gpu_vendor: Option<GpuVendor>,
+    swap_total_mb: Option<u64>,
+    swap_free_mb: Option<u64>,
+    load_average: Option<(f32, f32, f32)>,
+    machine_id: Option<Box<str>>,
+    boot_mode: BootMode,
+    secure_boot: Option<SecureBootStatus>,
+    root_filesystem: Option<Box<str>>,
+    tpm_available: bool,
+    battery_present: bool,
+    battery_percent: Option<u8>,
+    bluetooth_present: bool,
+    wifi_interfaces: Vec<Box<str>>,
+    locale: Option<Box<str>>,
+    timezone: Option<Box<str>>,
+    additional_package_managers: Vec<PackageManager>,
+    package_manager_locked: bool,
+}
+
+impl From<System> for Detected {
+    fn from(system: System) -> Self {
+        Self {
+            package_manager: system.package_manager(),
+            kernel_version: system.kernel_version().map(Into::into),
+            cpu_model: system.cpu_model().map(Into::into),
+            cpu_count: system.cpu_count(),
+            total_memory_mb: system.total_memory_mb(),
+            architecture: system.architecture().map(Into::into),
+            init_system: system.init_system(),
+            display_server: system.display_server(),
+            uptime_seconds: system.uptime_seconds(),
+            virt_env: system.virt_env(),
+            hostname: system.hostname().into(),
+            current_user: system.current_user().into(),
+            is_root: system.is_root(),
+            current_shell: system.current_shell().map(Into::into),
+            desktop_environment: system.desktop_environment(),
+            audio_server: system.audio_server(),
+            gpu_vendor: system.gpu_vendor(),
+            swap_total_mb: system.swap_total_mb(),
+            swap_free_mb: system.swap_free_mb(),
+            load_average: system.load_average(),
+            machine_id: system.machine_id().map(Into::into),
+            boot_mode: system.boot_mode(),
+            secure_boot: system.secure_boot(),
+            root_filesystem: system.root_filesystem().map(Into::into),
+            tpm_available: system.tpm_available(),
+            battery_present: system.battery_present(),
+            battery_percent: system.battery_percent(),
+            bluetooth_present: system.bluetooth_present(),
+            wifi_interfaces: system.wifi_interfaces().to_vec(),
+            locale: system.locale().map(Into::into),
+            timezone: system.timezone().map(Into::into),
+            additional_package_managers: system.additional_package_managers().to_vec(),
+            package_manager_locked: system.package_manager_locked(),
+            os_release: system.os_release,
+        }
+    }
+}
+
+impl From<Detected> for System {
+    fn from(detected: Detected) -> Self {
+        Self {
+            os_release: detected.os_release,
+            package_manager: detected.package_manager.into(),
+            kernel_version: detected.kernel_version.into(),
+            cpu: (detected.cpu_model, detected.cpu_count).into(),
+            memory: MemoryInfo {
+                total_mb: detected.total_memory_mb,
+                swap_total_mb: detected.swap_total_mb,
+                swap_free_mb: detected.swap_free_mb,
+            }
+            .into(),
+            architecture: detected.architecture.into(),
+            init_system: detected.init_system.into(),
+            display_server: detected.display_server.into(),
+            uptime_seconds: detected.uptime_seconds.into(),
+            virt_env: detected.virt_env.into(),
+            hostname: detected.hostname.into(),
+            current_user: detected.current_user.into(),
+            is_root: detected.is_root.into(),
+            current_shell: detected.current_shell.into(),
+            desktop_environment: detected.desktop_environment.into(),
+            audio_server: detected.audio_server.into(),
+            gpu_vendor: detected.gpu_vendor.into(),
+            load_average: detected.load_average.into(),
+            machine_id: detected.machine_id.into(),
+            boot_mode: detected.boot_mode.into(),
+            secure_boot: detected.secure_boot.into(),
+            root_filesystem: detected.root_filesystem.into(),
+            tpm_available: detected.tpm_available.into(),
+            battery: (detected.battery_present, detected.battery_percent).into(),
+            bluetooth_present: detected.bluetooth_present.into(),
+            wifi_interfaces: detected.wifi_interfaces.into(),
+            locale: detected.locale.into(),
+            timezone: detected.timezone.into(),
+            additional_package_managers: detected.additional_package_managers.into(),
+            package_manager_locked: detected.package_manager_locked.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum InitSystem {
+    Systemd,
+    OpenRC,
+    Runit,
+    S6,
+    SysVinit,
+    /// There is an init, but we don't recognize it
+    Unknown,
+}
+
+impl fmt::Display for InitSystem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Systemd => "systemd",
+            Self::OpenRC => "OpenRC",
+            Self::Runit => "runit",
+            Self::S6 => "s6",
+            Self::SysVinit => "SysVinit",
+            Self::Unknown => "unknown",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DisplayServer {
+    X11,
+    Wayland,
+    Mir,
+    /// There is a graphical session, but we don't recognize it
+    Unknown,
+}
+
+impl fmt::Display for DisplayServer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::X11 => "X11",
+            Self::Wayland => "Wayland",
+            Self::Mir => "Mir",
+            Self::Unknown => "unknown",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum VirtEnv {
+    /// Running directly on the hardware
+    None,
+    Kvm,
+    Qemu,
+    VirtualBox,
+    Vmware,
+    Xen,
+    Docker,
+    Podman,
+    Lxc,
+    Wsl,
+    /// Virtualized, but we don't recognize by what
+    Unknown,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DesktopEnvironment {
+    Gnome,
+    Kde,
+    Xfce,
+    Mate,
+    Cinnamon,
+    I3,
+    Sway,
+    Hyprland,
+    Cosmic,
+    /// There is a desktop, but we don't recognize it
+    Unknown,
+}
+
+impl fmt::Display for DesktopEnvironment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Gnome => "GNOME",
+            Self::Kde => "KDE Plasma",
+            Self::Xfce => "Xfce",
+            Self::Mate => "MATE",
+            Self::Cinnamon => "Cinnamon",
+            Self::I3 => "i3",
+            Self::Sway => "Sway",
+            Self::Hyprland => "Hyprland",
+            Self::Cosmic => "COSMIC",
+            Self::Unknown => "unknown",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AudioServer {
+    PipeWire,
+    PulseAudio,
+    /// No sound server, the programs use the sound cards directly
+    Alsa,
+    Jack,
+}
+
+impl fmt::Display for AudioServer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::PipeWire => "PipeWire",
+            Self::PulseAudio => "PulseAudio",
+            Self::Alsa => "ALSA",
+            Self::Jack => "JACK",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GpuVendor {
+    Nvidia,
+    Amd,
+    Intel,
+    /// A GPU by a vendor we don't recognize, like the virtual ones of VMs
+    Unknown,
+}
+
+impl fmt::Display for GpuVendor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Nvidia => "NVIDIA",
+            Self::Amd => "AMD",
+            Self::Intel => "Intel",
+            Self::Unknown => "unknown",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BootMode {
+    Uefi,
+    /// Legacy BIOS, or UEFI in its compatibility mode
+    Bios,
+    /// Without sysfs there's no telling, like in some containers
+    #[default]
+    Unknown,
+}
+
+impl fmt::Display for BootMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Uefi => "UEFI",
+            Self::Bios => "BIOS",
+            Self::Unknown => "unknown",
+        })
+    }
+}
+
+/// The Secure Boot variables of the UEFI firmware
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SecureBootStatus {
+    /// Only signed bootloaders and kernels are started
+    pub enabled: bool,
+    /// There are no keys enrolled yet, so anything can be, and nothing is checked
+    pub setup_mode: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DistroFamily {
+    Debian,
+    /// Fedora, RHEL and openSUSE, and the distributions based on them
+    Rpm,
+    Arch,
+    Gentoo,
+    Void,
+    /// A distribution that isn't based on any of the others
+    Unknown,
+}
+
+impl fmt::Display for DistroFamily {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Debian => "Debian",
+            Self::Rpm => "RPM",
+            Self::Arch => "Arch",
+            Self::Gentoo => "Gentoo",
+            Self::Void => "Void",
+            Self::Unknown => "unknown",
+        })
+    }
+}
+
+/// Maps the os-release `ID`s of distributions to the family they head
+const DISTRO_FAMILIES: [(&str, DistroFamily); 11] = [
+    ("debian", DistroFamily::Debian),
+    ("ubuntu", DistroFamily::Debian),
+    ("fedora", DistroFamily::Rpm),
+    ("rhel", DistroFamily::Rpm),
+    ("centos", DistroFamily::Rpm),
+    ("suse", DistroFamily::Rpm),
+    ("opensuse", DistroFamily::Rpm),
+    ("mageia", DistroFamily::Rpm),
+    ("arch", DistroFamily::Arch),
+    ("gentoo", DistroFamily::Gentoo),
+    ("void", DistroFamily::Void),
+];
+
+/// The reasons detecting the system can fail
+#[derive(Debug)]
+pub enum SystemInfoError {
+    /// None of the files describing the distribution could be read
+    MissingOsRelease,
+}
+
+impl fmt::Display for SystemInfoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingOsRelease => {
+                write!(f, "could not read any of {}", OS_RELEASE_PATHS.join(", "))
+            }
+        }
+    }
+}
+
+impl std::error::Error for SystemInfoError {}
+
+/// The files describing the distribution, in the order they are looked up. `/usr/lib/os-release`
+/// is the fallback defined by the os-release spec, `/etc/lsb-release` is all some older
+/// distributions ship
+const OS_RELEASE_PATHS: [&str; 3] = ["/etc/os-release", "/usr/lib/os-release", "/etc/lsb-release"];
+
+impl System {
+    pub fn info() -> Result<Self, SystemInfoError> {
+        let os_release = get_os_info();
+        if os_release == OsRelease::default() {
+            return Err(SystemInfoError::MissingOsRelease);
+        }
+        Ok(Self {
+            os_release,
+            package_manager: OnceLock::new(),
+            kernel_version: OnceLock::new(),
+            cpu: OnceLock::new(),
+            memory: OnceLock::new(),
+            architecture: OnceLock::new(),
+            init_system: OnceLock::new(),
+            display_server: OnceLock::new(),
+            uptime_seconds: OnceLock::new(),
+            virt_env: OnceLock::new(),
+            hostname: OnceLock::new(),
+            current_user: OnceLock::new(),
+            is_root: OnceLock::new(),
+            current_shell: OnceLock::new(),
+            desktop_environment: OnceLock::new(),
+            audio_server: OnceLock::new(),
+            gpu_vendor: OnceLock::new(),
+            load_average: OnceLock::new(),
+            machine_id: OnceLock::new(),
+            boot_mode: OnceLock::new(),
+            secure_boot: OnceLock::new(),
+            root_filesystem: OnceLock::new(),
+            tpm_available: OnceLock::new(),
+            battery: OnceLock::new(),
+            bluetooth_present: OnceLock::new(),
+            wifi_interfaces: OnceLock::new(),
+            locale: OnceLock::new(),
+            timezone: OnceLock::new(),
+            additional_package_managers: OnceLock::new(),
+            package_manager_locked: OnceLock::new(),
+        })
+    }
+
+    /// Detects everything again, for when the system changed under us, like after installing a
+    /// package manager. os-release is read right away, the rest on first access, like in `info()`.
+    /// If os-release can't be read anymore, nothing is changed
+    pub fn refresh(&mut self) -> Result<(), SystemInfoError> {
+        *self = Self::info()?;
+        Ok(())
+    }
+
+    /// Everything os-release says
+    pub fn os_release(&self) -> &OsRelease {
+        &self.os_release
+    }
+
+    /// The `ID` from os-release, e.g. `arch`
+    pub fn id(&self) -> &str {
+        &self.os_release.id
+    }
+
+    /// The `ID`, without the version or edition some distributions put in it, e.g. `ubuntu` for
+    /// `ubuntu-22.04`. It's what scripts should compare against, see `packagemanagers::normalize_id`
+    pub fn comparable_id(&self) -> Cow<'_, str> {
+        packagemanagers::normalize_id(&self.os_release.id)
+    }
+
+    /// The `ID_LIKE` from os-release, the distributions this one is based on, closest first
+    pub fn id_like(&self) -> &[Box<str>] {
+        &self.os_release.id_like
+    }
+
+    /// The `PRETTY_NAME` from os-release, e.g. `Arch Linux`
+    pub fn pretty_name(&self) -> &str {
+        &self.os_release.pretty_name
+    }
+
+    /// The family of the distribution, by its `ID`, then by each entry of its `ID_LIKE`, like
+    /// `get_package_manager()`. `None` if os-release has no `ID` to go by
+    pub fn distro_family(&self) -> Option<DistroFamily> {
+        if self.os_release.id.is_empty() {
+            return None;
+        }
+        let family = std::iter::once(&self.os_release.id)
+            .chain(&self.os_release.id_like)
+            .find_map(|id| {
+                DISTRO_FAMILIES
+                    .iter()
+                    .find(|(distro, _)| **distro == **id)
+                    .map(|(_, family)| *family)
+            })
+            .unwrap_or(DistroFamily::Unknown);
+        Some(family)
+    }
+
+    /// The native package manager of the distribution, `None` if we don't know it
+    pub fn package_manager(&self) -> Option<PackageManager> {
+        *self.package_manager.get_or_init(|| {
+            get_package_manager(&self.os_release.id, &self.os_release.id_like)
+                .filter(PackageManager::is_available)
+        })
+    }
+
+    /// The release of the running kernel, e.g. `6.9.7-arch1-1`
+    pub fn kernel_version(&self) -> Option<&str> {
+        self.kernel_version
+            .get_or_init(get_kernel_version)
+            .as_deref()
+    }
+
+    /// The CPU model name, e.g. `AMD Ryzen 7 5800X 8-Core Processor`
+    pub fn cpu_model(&self) -> Option<&str> {
+        self.cpu.get_or_init(get_cpu_info).0.as_deref()
+    }
+
+    /// The number of logical processors
+    pub fn cpu_count(&self) -> Option<u32> {
+        self.cpu.get_or_init(get_cpu_info).1
+    }
+
+    fn memory(&self) -> MemoryInfo {
+        *self.memory.get_or_init(|| {
+            let Ok(meminfo) = fs::read_to_string("/proc/meminfo") else {
+                return MemoryInfo::default();
+            };
+            let (swap_total_mb, swap_free_mb) = parse_swap_info(&meminfo);
+            MemoryInfo {
+                total_mb: parse_total_memory(&meminfo),
+                swap_total_mb,
+                swap_free_mb,
+            }
+        })
+    }
+
+    /// Total usable RAM in mebibytes
+    pub fn total_memory_mb(&self) -> Option<u64> {
+        self.memory().total_mb
+    }
+
+    /// The machine architecture, using Rust target triple names, e.g. `x86_64` or `aarch64`
+    pub fn architecture(&self) -> Option<&str> {
+        self.architecture.get_or_init(get_architecture).as_deref()
+    }
+
+    /// The init system managing services, so scripts know whether to use `systemctl`, `rc-service`
+    /// or `sv`
+    pub fn init_system(&self) -> Option<InitSystem> {
+        *self.init_system.get_or_init(detect_init_system)
+    }
+
+    /// The display server of the graphical session we are running in, `None` outside of one
+    pub fn display_server(&self) -> Option<DisplayServer> {
+        *self.display_server.get_or_init(detect_display_server)
+    }
+
+    /// Seconds since boot, as of the first time it was asked for
+    pub fn uptime_seconds(&self) -> Option<u64> {
+        *self.uptime_seconds.get_or_init(get_uptime)
+    }
+
+    /// The virtual machine or container we are running in, if we could tell
+    pub fn virt_env(&self) -> Option<VirtEnv> {
+        *self.virt_env.get_or_init(detect_virt)
+    }
+
+    /// The name of this machine, `unknown` if it has none
+    pub fn hostname(&self) -> &str {
+        self.hostname.get_or_init(get_hostname)
+    }
+
+    /// The name of the user running linutil, `unknown` if we couldn't find it
+    pub fn current_user(&self) -> &str {
+        self.current_user.get_or_init(get_current_user)
+    }
+
+    /// Whether linutil is running with root privileges
+    pub fn is_root(&self) -> bool {
+        // SAFETY: geteuid can't fail and has no preconditions
+        *self.is_root.get_or_init(|| unsafe { libc::geteuid() } == 0)
+    }
+
+    /// The shell of the user, as a bare name like `bash` or `zsh`
+    pub fn current_shell(&self) -> Option<&str> {
+        self.current_shell.get_or_init(get_current_shell).as_deref()
+    }
+
+    /// The desktop environment or window manager of the graphical session, `None` outside of one
+    pub fn desktop_environment(&self) -> Option<DesktopEnvironment> {
+        *self
+            .desktop_environment
+            .get_or_init(detect_desktop_environment)
+    }
+
+    /// The sound server, or just ALSA if there is none running
+    pub fn audio_server(&self) -> Option<AudioServer> {
+        *self.audio_server.get_or_init(detect_audio_server)
+    }
+
+    /// The vendor of the GPU the system booted with, see `detect_gpu_vendors` for all of them
+    pub fn gpu_vendor(&self) -> Option<GpuVendor> {
+        *self.gpu_vendor.get_or_init(detect_primary_gpu_vendor)
+    }
+
+    /// Total swap in mebibytes, 0 if there's no swap configured
+    pub fn swap_total_mb(&self) -> Option<u64> {
+        self.memory().swap_total_mb
+    }
+
+    /// Unused swap in mebibytes, as of the first time memory was asked for
+    pub fn swap_free_mb(&self) -> Option<u64> {
+        self.memory().swap_free_mb
+    }
+
+    /// The load averages over the last 1, 5 and 15 minutes
+    pub fn load_average(&self) -> Option<(f32, f32, f32)> {
+        *self.load_average.get_or_init(get_load_average)
+    }
+
+    /// The stable id of this installation, from `/etc/machine-id`. Containers often have none
+    pub fn machine_id(&self) -> Option<&str> {
+        self.machine_id.get_or_init(get_machine_id).as_deref()
+    }
+
+    /// How the firmware booted the system, which decides how it has to be partitioned and where the
+    /// bootloader goes
+    pub fn boot_mode(&self) -> BootMode {
+        *self.boot_mode.get_or_init(detect_boot_mode)
+    }
+
+    /// Whether the firmware only boots signed code, `None` without UEFI. See `detect_secure_boot`
+    pub fn secure_boot(&self) -> Option<SecureBootStatus> {
+        *self.secure_boot.get_or_init(detect_secure_boot)
+    }
+
+    /// The type of the filesystem mounted on `/`, like `ext4` or `btrfs`, as the kernel names it
+    pub fn root_filesystem(&self) -> Option<&str> {
+        self.root_filesystem
+            .get_or_init(get_root_filesystem)
+            .as_deref()
+    }
+
+    /// Whether `/` is btrfs and has a `/.snapshots` subvolume, like snapper sets up. Not cached,
+    /// since scripts create it
+    pub fn has_btrfs_snapshots(&self) -> bool {
+        self.root_filesystem() == Some("btrfs") && Path::new("/.snapshots").is_dir()
+    }
+
+    /// Whether there's a TPM 2.0, for unlocking encrypted disks and attestation
+    pub fn tpm_available(&self) -> bool {
+        *self.tpm_available.get_or_init(detect_tpm)
+    }
+
+    /// Whether this is something with a battery, like a laptop
+    pub fn battery_present(&self) -> bool {
+        self.battery.get_or_init(get_battery_info).0
+    }
+
+    /// How full the battery is, as of the first time it was asked for
+    pub fn battery_percent(&self) -> Option<u8> {
+        self.battery.get_or_init(get_battery_info).1
+    }
+
+    /// Whether there's a Bluetooth adapter the kernel has a driver for
+    pub fn bluetooth_present(&self) -> bool {
+        *self.bluetooth_present.get_or_init(detect_bluetooth)
+    }
+
+    /// The names of the wireless network interfaces, like `wlan0` or `wlp3s0`
+    pub fn wifi_interfaces(&self) -> &[Box<str>] {
+        self.wifi_interfaces.get_or_init(get_wifi_interfaces)
+    }
+
+    /// Whether there's a wireless network interface
+    pub fn wifi_present(&self) -> bool {
+        !self.wifi_interfaces().is_empty()
+    }
+
+    /// The locale linutil runs in, like `en_US.UTF-8`
+    pub fn locale(&self) -> Option<&str> {
+        self.locale.get_or_init(get_locale).as_deref()
+    }
+
+    /// The timezone, as a name from the tz database like `Europe/Berlin`
+    pub fn timezone(&self) -> Option<&str> {
+        self.timezone.get_or_init(get_timezone).as_deref()
+    }
+
+    /// Whether `cmd` can be run, like `command -v` in a script. It's looked up on `PATH` every
+    /// time, since scripts install things
+    pub fn has_command(&self, cmd: &str) -> bool {
+        has_command_in(cmd, &env::var_os("PATH").unwrap_or_default())
+    }
+
+    /// Package managers for applications that are installed besides the native one, like Flatpak
+    pub fn additional_package_managers(&self) -> &[PackageManager] {
+        self.additional_package_managers
+            .get_or_init(packagemanagers::get_additional_managers)
+    }
+
+    /// Whether another process is using the native package manager, so it can't be run right now
+    pub fn package_manager_locked(&self) -> bool {
+        *self
+            .package_manager_locked
+            .get_or_init(|| self.package_manager().is_some_and(|pm| pm.is_locked()))
+    }
+}
+
+/// Two systems are the same if they're the same distribution on the same architecture, which is
+/// what decides how scripts behave on them. The rest is left out, it changes too often, or is
+/// only there to be displayed
+impl PartialEq for System {
+    fn eq(&self, other: &Self) -> bool {
+        self.os_release.id == other.os_release.id && self.architecture() == other.architecture()
+    }
+}
+
+impl Eq for System {}
+
+impl std::hash::Hash for System {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.os_release.id.hash(state);
+        self.architecture().hash(state);
+    }
+}
+
+/// Everything, detected like for serializing, instead of the fields that weren't asked for yet
+/// showing up as uninitialized
+impl fmt::Debug for System {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Detected::from(self.clone()).fmt(f)
+    }
+}
+
+/// A human readable summary, meant for bug reports and `linutil --sysinfo`
+impl fmt::Display for System {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Distribution: {} ({})",
+            self.os_release.pretty_name, self.os_release.id
+        )?;
+        writeln!(f, "Package manager: {}", OrUnknown(self.package_manager()))?;
+        writeln!(f, "Kernel: {}", OrUnknown(self.kernel_version()))?;
+        writeln!(f, "Architecture: {}", OrUnknown(self.architecture()))?;
+        write!(f, "CPU: {}", OrUnknown(self.cpu_model()))?;
+        if let Some(count) = self.cpu_count() {
+            let threads = if count == 1 { "thread" } else { "threads" };
+            write!(f, " ({count} {threads})")?;
+        }
+        writeln!(f)?;
+        match self.total_memory_mb() {
+            Some(memory) => writeln!(f, "Memory: {memory} MiB")?,
+            None => writeln!(f, "Memory: unknown")?,
+        }
+        writeln!(f, "Init system: {}", OrUnknown(self.init_system()))?;
+        match self.display_server() {
+            Some(display_server) => write!(f, "Display server: {display_server}"),
+            None => write!(f, "Display server: none"),
+        }
+    }
+}
+
+/// Displays the value, or `unknown` if there is none
+struct OrUnknown<T>(Option<T>);
+
+impl<T: fmt::Display> fmt::Display for OrUnknown<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.0 {
+            Some(value) => value.fmt(f),
+            None => f.write_str("unknown"),
+        }
+    }
+}
+
+/// The fields of os-release, see os-release(5). The ones a distribution leaves out are empty
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OsRelease {
+    /// `ID`, e.g. `arch`
+    pub id: Box<str>,
+    /// `ID_LIKE`, the distributions this one is based on, closest first
+    pub id_like: Vec<Box<str>>,
+    /// `PRETTY_NAME`, e.g. `Arch Linux`
+    pub pretty_name: Box<str>,
+    /// `VERSION_ID`, e.g. `24.04`. Rolling releases have none
+    pub version_id: Option<Box<str>>,
+    /// `BUILD_ID`, the image the system was installed from
+    pub build_id: Option<Box<str>>,
+    /// `VARIANT_ID`, the edition, e.g. `workstation` on Fedora
+    pub variant_id: Option<Box<str>>,
+    pub home_url: Option<Box<str>>,
+    pub bug_report_url: Option<Box<str>>,
+    /// Every other key, lowercase, like `name` or `version_codename`
+    pub extra: HashMap<Box<str>, Box<str>>,
+}
+
+impl OsRelease {
+    /// `VERSION_ID` as a semver, for checks like "Ubuntu 22.04 or newer". The parts a distribution
+    /// leaves out are 0, so `22.04` is `22.4.0` and `38` is `38.0.0`. `None` if there's no version,
+    /// or it isn't made of numbers
+    #[cfg(feature = "semver")]
+    pub fn version_id_semver(&self) -> Option<semver::Version> {
+        let (major, minor, patch) = parse_version_id(self.version_id.as_deref()?)?;
+        Some(semver::Version::new(major, minor, patch))
+    }
+}
+
+#[cfg(feature = "semver")]
+fn parse_version_id(version_id: &str) -> Option<(u64, u64, u64)> {
+    let mut parts = version_id.split('.').map(|part| part.parse::<u64>().ok());
+    let major = parts.next()??;
+    let minor = parts.next().unwrap_or(Some(0))?;
+    let patch = parts.next().unwrap_or(Some(0))?;
+    // Some versions have more parts than that, which can't be compared like this
+    parts.next().is_none().then_some((major, minor, patch))
+}
+
+impl From<HashMap<Box<str>, Box<str>>> for OsRelease {
+    fn from(mut fields: HashMap<Box<str>, Box<str>>) -> Self {
+        let mut take = |key: &str| fields.remove(key);
+        Self {
+            id: take("id").unwrap_or_default(),
+            id_like: take("id_like")
+                .map(|id_like| id_like.split_whitespace().map(Into::into).collect())
+                .unwrap_or_default(),
+            pretty_name: take("pretty_name").unwrap_or_default(),
+            version_id: take("version_id"),
+            build_id: take("build_id"),
+            variant_id: take("variant_id"),
+            home_url: take("home_url"),
+            bug_report_url: take("bug_report_url"),
+            extra: fields,
+        }
+    }
+}
+
+/// Reads the first os-release file found, with lowercase keys and unquoted values. It's empty if
+/// there is none
+fn get_os_info() -> OsRelease {
+    let Some(path) = locate_os_release() else {
+        return OsRelease::default();
+    };
+    let Ok(contents) = fs::read_to_string(&path) else {
+        return OsRelease::default();
+    };
+    let os_info = parse_os_release(&contents);
+    if path.ends_with("lsb-release") {
+        lsb_to_os_release(os_info).into()
+    } else {
+        os_info.into()
+    }
+}
+
+/// Finds the first of `OS_RELEASE_PATHS` that exists
+fn locate_os_release() -> Option<PathBuf> {
+    first_existing(&OS_RELEASE_PATHS)
+}
+
+fn first_existing<P: AsRef<Path>>(paths: &[P]) -> Option<PathBuf> {
+    paths
+        .iter()
+        .map(AsRef::as_ref)
+        .find(|path| path.is_file())
+        .map(Path::to_path_buf)
+}
+
+/// `/etc/lsb-release` uses its own keys (`DISTRIB_ID=Ubuntu`), rename them to the os-release ones
+fn lsb_to_os_release(lsb: HashMap<Box<str>, Box<str>>) -> HashMap<Box<str>, Box<str>> {
+    lsb.into_iter()
+        .filter_map(|(key, value)| match key.as_ref() {
+            "distrib_id" => Some(("id".into(), value.to_lowercase().into())),
+            "distrib_description" => Some(("pretty_name".into(), value)),
+            "distrib_release" => Some(("version_id".into(), value)),
+            "distrib_codename" => Some(("version_codename".into(), value)),
+            _ => None,
+        })
+        .collect()
+}
+
+fn parse_os_release(contents: &str) -> HashMap<Box<str>, Box<str>> {
+    contents
+        .lines()
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| {
+            let value = value.trim().trim_matches(|c| c == '"' || c == '\'');
+            (key.trim().to_lowercase().into(), value.into())
+        })
+        .collect()
+}
+
+/// Looks up the package manager of the distribution by its normalized `ID`, then by each entry of
+/// the space-separated `ID_LIKE` list, so derivatives like Pop!_OS (`ID_LIKE="ubuntu debian"`)
+/// resolve to the package manager of the distribution they are based on
+fn get_package_manager(id: &str, id_like: &[Box<str>]) -> Option<PackageManager> {
+    packagemanagers::get(&packagemanagers::normalize_id(id))
+        .or_else(|| id_like.iter().find_map(|like| packagemanagers::get(like)))
+}
+
+/// Like the shell, a command with a `/` in it is a path to the file, and isn't looked up
+fn has_command_in(cmd: &str, path: &std::ffi::OsStr) -> bool {
+    if cmd.contains('/') {
+        packagemanagers::is_executable(Path::new(cmd))
+    } else {
+        !cmd.is_empty() && packagemanagers::is_in_path(cmd, path)
+    }
+}
+
+/// Reads the release of the running kernel from `/proc/version`
+pub fn get_kernel_version() -> Option<Box<str>> {
+    let contents = fs::read_to_string("/proc/version").ok()?;
+    parse_kernel_version(&contents)
+}
+
+/// `/proc/version` looks like `Linux version 6.9.7-arch1-1 (linux@archlinux) (gcc ...) #1 SMP ...`,
+/// the release is the first token after `Linux version`
+fn parse_kernel_version(contents: &str) -> Option<Box<str>> {
+    let release = contents
+        .strip_prefix("Linux version ")?
+        .split_whitespace()
+        .next()?;
+    // Make sure it actually starts like a version number
+    release
+        .starts_with(|c: char| c.is_ascii_digit())
+        .then(|| release.into())
+}
+
+/// Reads the CPU model and the number of logical processors from `/proc/cpuinfo`
+pub fn get_cpu_info() -> (Option<Box<str>>, Option<u32>) {
+    match fs::read_to_string("/proc/cpuinfo") {
+        Ok(contents) => parse_cpu_info(&contents),
+        Err(_) => (None, None),
+    }
+}
+
+/// x86_64 has a `model name` in every processor block, aarch64 usually has none and only names
+/// the board in a trailing `Model` (or `Hardware`) line, so we take whichever comes first
+fn parse_cpu_info(contents: &str) -> (Option<Box<str>>, Option<u32>) {
+    let mut model = None;
+    let mut count = 0;
+    for (key, value) in contents.lines().filter_map(|line| line.split_once(':')) {
+        match key.trim() {
+            "processor" => count += 1,
+            "model name" | "Model" | "Hardware" if model.is_none() && !value.trim().is_empty() => {
+                model = Some(value.trim().into())
+            }
+            _ => {}
+        }
+    }
+    (model, (count > 0).then_some(count))
+}
+
+/// Reads the total usable RAM from `/proc/meminfo`, in mebibytes
+pub fn get_total_memory() -> Option<u64> {
+    let contents = fs::read_to_string("/proc/meminfo").ok()?;
+    parse_total_memory(&contents)
+}
+
+fn parse_total_memory(meminfo: &str) -> Option<u64> {
+    parse_meminfo_field(meminfo, "MemTotal").map(|kib| kib / 1024)
+}
+
+/// Reads the total and the free swap from `/proc/meminfo`, in mebibytes
+pub fn get_swap_info() -> (Option<u64>, Option<u64>) {
+    match fs::read_to_string("/proc/meminfo") {
+        Ok(contents) => parse_swap_info(&contents),
+        Err(_) => (None, None),
+    }
+}
+
+fn parse_swap_info(meminfo: &str) -> (Option<u64>, Option<u64>) {
+    let field = |name| parse_meminfo_field(meminfo, name).map(|kib| kib / 1024);
+    (field("SwapTotal"), field("SwapFree"))
+}
+
+/// Finds a `Field:   1234 kB` line in `/proc/meminfo` and returns its value in kibibytes
+fn parse_meminfo_field(contents: &str, field: &str) -> Option<u64> {
+    contents
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .find(|(key, _)| *key == field)?
+        .1
+        .trim()
+        .trim_end_matches("kB")
+        .trim()
+        .parse()
+        .ok()
+}
+
+/// Detects the machine architecture. `/proc/sys/kernel/arch` only exists on newer kernels, so if
+/// it's missing we look for an architecture name in the kernel release in `/proc/version`
+pub fn get_architecture() -> Option<Box<str>> {
+    let from_arch = fs::read_to_string("/proc/sys/kernel/arch")
+        .ok()
+        .and_then(|arch| normalize_architecture(arch.trim()));
+    let arch = match from_arch {
+        Some(arch) => arch,
+        None => {
+            let version = fs::read_to_string("/proc/version").ok()?;
+            find_architecture(&version)?
+        }
+    };
+    Some(arch.into())
+}
+
+/// Finds the first thing resembling an architecture in the output of `uname -a` or `/proc/version`,
+/// kernel releases on some distributions end in it, e.g. `6.8.5-301.fc40.x86_64`
+fn find_architecture(version: &str) -> Option<&'static str> {
+    version
+        .split(|c: char| c.is_whitespace() || matches!(c, '.' | '-' | '(' | ')' | '+'))
+        .find_map(normalize_architecture)
+}
+
+/// Maps the architecture names used by `uname -m` and distributions to Rust target triple names
+fn normalize_architecture(arch: &str) -> Option<&'static str> {
+    let arch = match arch {
+        "x86_64" | "amd64" => "x86_64",
+        "i386" | "i486" | "i586" | "i686" | "x86" => "x86",
+        "aarch64" | "arm64" | "armv8l" => "aarch64",
+        "arm" | "armv6l" | "armv7l" | "armhf" => "arm",
+        "riscv64" => "riscv64",
+        "loongarch64" | "loong64" => "loongarch64",
+        "ppc64le" | "ppc64" | "powerpc64" => "powerpc64",
+        "s390x" => "s390x",
+        "mips64" => "mips64",
+        _ => return None,
+    };
+    Some(arch)
+}
+
+/// Detects the init system by the runtime directories each of them creates, falling back to
+/// asking `/sbin/init` itself
+pub fn detect_init_system() -> Option<InitSystem> {
+    let markers = [
+        ("/run/systemd/private", InitSystem::Systemd),
+        ("/run/openrc", InitSystem::OpenRC),
+        ("/run/runit", InitSystem::Runit),
+        ("/run/s6", InitSystem::S6),
+    ];
+    if let Some((_, init)) = markers.iter().find(|(path, _)| Path::new(path).exists()) {
+        return Some(*init);
+    }
+
+    let output = Command::new("/sbin/init").arg("--version").output().ok()?;
+    // SysVinit prints its version to stderr
+    let version = [output.stdout, output.stderr].concat();
+    Some(parse_init_version(&String::from_utf8_lossy(&version)))
+}
+
+fn parse_init_version(version: &str) -> InitSystem {
+    if version.contains("systemd") {
+        InitSystem::Systemd
+    } else if version.contains("SysV") {
+        InitSystem::SysVinit
+    } else {
+        InitSystem::Unknown
+    }
+}
+
+/// Detects the display server from the session environment. If the environment doesn't tell us
+/// (e.g. we were started over ssh or from a tty), a wayland socket in the runtime directory of the
+/// user still means a wayland session is running
+pub fn detect_display_server() -> Option<DisplayServer> {
+    let var = |name| {
+        env::var(name)
+            .ok()
+            .filter(|value: &String| !value.is_empty())
+    };
+    display_server_from_env(
+        var("WAYLAND_DISPLAY").as_deref(),
+        var("MIR_SOCKET").as_deref(),
+        var("DISPLAY").as_deref(),
+        var("XDG_SESSION_TYPE").as_deref(),
+    )
+    .or_else(|| has_wayland_socket().then_some(DisplayServer::Wayland))
+}
+
+fn display_server_from_env(
+    wayland_display: Option<&str>,
+    mir_socket: Option<&str>,
+    display: Option<&str>,
+    session_type: Option<&str>,
+) -> Option<DisplayServer> {
+    // XWayland sets DISPLAY too, so wayland has to be checked first
+    if wayland_display.is_some() || session_type == Some("wayland") {
+        return Some(DisplayServer::Wayland);
+    }
+    if mir_socket.is_some() || session_type == Some("mir") {
+        return Some(DisplayServer::Mir);
+    }
+    if display.is_some() || session_type == Some("x11") {
+        return Some(DisplayServer::X11);
+    }
+    match session_type {
+        None | Some("tty" | "unspecified") => None,
+        Some(_) => Some(DisplayServer::Unknown),
+    }
+}
+
+fn has_wayland_socket() -> bool {
+    let Some(uid) = get_uid() else {
+        return false;
+    };
+    let Ok(entries) = fs::read_dir(format!("/run/user/{uid}")) else {
+        return false;
+    };
+    entries.flatten().any(|entry| {
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        name.starts_with("wayland-") && !name.ends_with(".lock")
+    })
+}
+
+/// Reads the real user id of this process from `/proc/self/status`
+fn get_uid() -> Option<u32> {
+    let status = fs::read_to_string("/proc/self/status").ok()?;
+    status
+        .lines()
+        .find_map(|line| line.strip_prefix("Uid:"))?
+        .split_whitespace()
+        .next()?
+        .parse()
+        .ok()
+}
+
+/// Reads the number of seconds since boot from `/proc/uptime`
+pub fn get_uptime() -> Option<u64> {
+    let contents = fs::read_to_string("/proc/uptime").ok()?;
+    parse_uptime(&contents)
+}
+
+/// Parses the contents of `/proc/uptime`: the seconds since boot, followed by the idle time of all
+/// CPUs. Fractions of a second are dropped
+///
+/// ```
+/// use tui::systeminfo::parse_uptime;
+///
+/// assert_eq!(parse_uptime("350735.47 234388.90\n"), Some(350735));
+/// assert_eq!(parse_uptime(""), None);
+/// ```
+pub fn parse_uptime(contents: &str) -> Option<u64> {
+    let seconds: f64 = contents.split_whitespace().next()?.parse().ok()?;
+    Some(seconds as u64)
+}
+
+/// Reads the load averages over the last 1, 5 and 15 minutes from `/proc/loadavg`
+pub fn get_load_average() -> Option<(f32, f32, f32)> {
+    let contents = fs::read_to_string("/proc/loadavg").ok()?;
+    parse_load_average(&contents)
+}
+
+/// `/proc/loadavg` looks like `0.52 0.58 0.59 1/1141 48213`, the rest after the averages are the
+/// running and total processes, and the last pid
+fn parse_load_average(contents: &str) -> Option<(f32, f32, f32)> {
+    let mut averages = contents.split_whitespace().map(str::parse);
+    match (averages.next()?, averages.next()?, averages.next()?) {
+        (Ok(one), Ok(five), Ok(fifteen)) => Some((one, five, fifteen)),
+        _ => None,
+    }
+}
+
+/// Detects whether we are running in a container or a virtual machine. Containers are checked
+/// first, since a container can run inside of a VM, but it's the container scripts care about.
+/// Returns `None` if there was nothing we could read to tell
+pub fn detect_virt() -> Option<VirtEnv> {
+    if Path::new("/.dockerenv").exists() {
+        return Some(VirtEnv::Docker);
+    }
+    if Path::new("/.containerenv").exists() {
+        return Some(VirtEnv::Podman);
+    }
+    // Only readable as root, but it's how systemd-detect-virt recognizes LXC
+    if let Some(virt) = fs::read("/proc/1/environ")
+        .ok()
+        .and_then(|environ| virt_from_environ(&environ))
+    {
+        return Some(virt);
+    }
+    if fs::read_to_string("/proc/version")
+        .is_ok_and(|version| version.contains("microsoft") || version.contains("WSL"))
+    {
+        return Some(VirtEnv::Wsl);
+    }
+    if fs::read_to_string("/sys/hypervisor/type").is_ok_and(|kind| kind.trim() == "xen") {
+        return Some(VirtEnv::Xen);
+    }
+    // The hypervisor flag only tells us that we are virtualized, the firmware tells us by what
+    let dmi = ["sys_vendor", "product_name"]
+        .iter()
+        .filter_map(|file| fs::read_to_string(format!("/sys/class/dmi/id/{file}")).ok())
+        .collect::<String>();
+    if let Some(virt) = virt_from_dmi(&dmi) {
+        return Some(virt);
+    }
+    let cpuinfo = fs::read_to_string("/proc/cpuinfo").ok()?;
+    if has_hypervisor_flag(&cpuinfo) {
+        Some(VirtEnv::Unknown)
+    } else {
+        Some(VirtEnv::None)
+    }
+}
+
+/// Looks for the `container=` variable container managers set for the init of the container
+fn virt_from_environ(environ: &[u8]) -> Option<VirtEnv> {
+    environ
+        .split(|&byte| byte == 0)
+        .find_map(|var| var.strip_prefix(b"container="))
+        .map(|container| match container {
+            b"docker" => VirtEnv::Docker,
+            b"podman" => VirtEnv::Podman,
+            b"lxc" | b"lxc-libvirt" => VirtEnv::Lxc,
+            _ => VirtEnv::Unknown,
+        })
+}
+
+fn virt_from_dmi(dmi: &str) -> Option<VirtEnv> {
+    let vendors = [
+        ("KVM", VirtEnv::Kvm),
+        ("QEMU", VirtEnv::Qemu),
+        ("VirtualBox", VirtEnv::VirtualBox),
+        ("innotek GmbH", VirtEnv::VirtualBox),
+        ("VMware", VirtEnv::Vmware),
+        ("Xen", VirtEnv::Xen),
+    ];
+    vendors
+        .iter()
+        .find(|(vendor, _)| dmi.contains(vendor))
+        .map(|(_, virt)| *virt)
+}
+
+fn has_hypervisor_flag(cpuinfo: &str) -> bool {
+    cpuinfo
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .filter(|(key, _)| key.trim() == "flags")
+        .any(|(_, flags)| flags.split_whitespace().any(|flag| flag == "hypervisor"))
+}
+
+/// Reads the hostname from `/etc/hostname`, falling back to asking the kernel
+pub fn get_hostname() -> Box<str> {
+    read_hostname(Path::new("/etc/hostname"))
+        .or_else(gethostname)
+        .unwrap_or_else(|| "unknown".into())
+}
+
+fn read_hostname(path: &Path) -> Option<Box<str>> {
+    let contents = fs::read_to_string(path).ok()?;
+    let hostname = contents.trim();
+    (!hostname.is_empty()).then(|| hostname.into())
+}
+
+/// Safe wrapper around `gethostname(2)`
+fn gethostname() -> Option<Box<str>> {
+    // HOST_NAME_MAX is 64 on Linux, the extra byte leaves room for the terminating nul
+    let mut buf = [0u8; 65];
+    // SAFETY: the pointer and length describe `buf`, which outlives the call
+    let result = unsafe { libc::gethostname(buf.as_mut_ptr().cast(), buf.len()) };
+    if result != 0 {
+        return None;
+    }
+    let len = buf.iter().position(|&byte| byte == 0)?;
+    let hostname = std::str::from_utf8(&buf[..len]).ok()?;
+    (!hostname.is_empty()).then(|| hostname.into())
+}
+
+/// Reads the machine id from `/etc/machine-id`
+pub fn get_machine_id() -> Option<Box<str>> {
+    read_machine_id(Path::new("/etc/machine-id"))
+}
+
+/// A machine id is 32 hex digits. Images that leave it to the first boot contain nothing, or
+/// `uninitialized`, which isn't an id yet
+fn read_machine_id(path: &Path) -> Option<Box<str>> {
+    let contents = fs::read_to_string(path).ok()?;
+    let id = contents.trim();
+    (id.len() == 32 && id.bytes().all(|byte| byte.is_ascii_hexdigit())).then(|| id.into())
+}
+
+/// Gets the locale from `$LC_ALL`, which overrides everything else, then `$LANG`, then the one
+/// the system is set to in `/etc/locale.conf`
+pub fn get_locale() -> Option<Box<str>> {
+    locale_from(env::var("LC_ALL").ok(), env::var("LANG").ok())
+        .or_else(|| parse_locale_file(Path::new("/etc/locale.conf")))
+}
+
+/// The locale the values of `$LC_ALL` and `$LANG` say, an empty one is the same as none
+fn locale_from(lc_all: Option<String>, lang: Option<String>) -> Option<Box<str>> {
+    [lc_all, lang]
+        .into_iter()
+        .flatten()
+        .find(|locale| !locale.is_empty())
+        .map(Into::into)
+}
+
+/// `locale.conf` is a list of `LANG=en_US.UTF-8` lines, for each of the `LC_` categories too. The
+/// value of `LANG` is the locale, the others are exceptions to it
+pub fn parse_locale_file(path: &Path) -> Option<Box<str>> {
+    let contents = fs::read_to_string(path).ok()?;
+    contents.lines().find_map(|line| {
+        let locale = line.trim().strip_prefix("LANG=")?.trim_matches(['"', '\'']);
+        (!locale.is_empty()).then(|| locale.into())
+    })
+}
+
+/// Gets the timezone from `$TZ`, then `/etc/timezone`, which Debian has, then the zone
+/// `/etc/localtime` links to, which is what everything else, and systemd, use
+pub fn get_timezone() -> Option<Box<str>> {
+    timezone_from_env(env::var("TZ").ok())
+        .or_else(|| read_timezone_file(Path::new("/etc/timezone")))
+        .or_else(|| timezone_from_link(Path::new("/etc/localtime")))
+}
+
+/// The timezone the value of `$TZ` says
+fn timezone_from_env(tz: Option<String>) -> Option<Box<str>> {
+    // A leading `:` means the rest is a file in the zoneinfo directory
+    let tz = tz?;
+    let tz = tz.trim_start_matches(':');
+    (!tz.is_empty()).then(|| tz.into())
+}
+
+fn read_timezone_file(path: &Path) -> Option<Box<str>> {
+    let contents = fs::read_to_string(path).ok()?;
+    let timezone = contents.trim();
+    (!timezone.is_empty()).then(|| timezone.into())
+}
+
+/// The link goes to `/usr/share/zoneinfo/<zone>`, though some distributions make it relative, or
+/// have the zones somewhere else
+fn timezone_from_link(path: &Path) -> Option<Box<str>> {
+    let target = fs::read_link(path).ok()?;
+    let target = target.to_str()?;
+    let (_, timezone) = target.split_once("zoneinfo/")?;
+    (!timezone.is_empty()).then(|| timezone.into())
+}
+
+/// Gets the name of the current user from `$USER`, or by looking our uid up in `/etc/passwd`
+pub fn get_current_user() -> Box<str> {
+    let from_passwd = || {
+        let uid = get_uid()?;
+        let passwd = fs::read_to_string("/etc/passwd").ok()?;
+        user_from_passwd(&passwd, uid)
+    };
+    user_from_env(env::var("USER").ok())
+        .or_else(from_passwd)
+        .unwrap_or_else(|| "unknown".into())
+}
+
+/// `$USER` is left empty by some minimal shells, which is the same as not setting it
+fn user_from_env(user: Option<String>) -> Option<Box<str>> {
+    user.filter(|user| !user.is_empty()).map(Into::into)
+}
+
+/// Finds the name of the user with this uid, passwd lines look like
+/// `name:password:uid:gid:comment:home:shell`
+fn user_from_passwd(passwd: &str, uid: u32) -> Option<Box<str>> {
+    passwd.lines().find_map(|line| {
+        let mut fields = line.split(':');
+        let name = fields.next()?;
+        let entry_uid: u32 = fields.nth(1)?.parse().ok()?;
+        (entry_uid == uid).then(|| name.into())
+    })
+}
+
+/// Shells we recognize when guessing the shell from the process that started linutil
+const KNOWN_SHELLS: [&str; 10] = [
+    "bash", "zsh", "fish", "dash", "sh", "ksh", "mksh", "tcsh", "csh", "nu",
+];
+
+/// Gets the shell of the user from `$SHELL`, or if that isn't set, from the name of the process
+/// that started linutil
+pub fn get_current_shell() -> Option<Box<str>> {
+    if let Some(shell) = env::var("SHELL")
+        .ok()
+        .and_then(|shell| normalize_shell(&shell).map(Into::into))
+    {
+        return Some(shell);
+    }
+
+    let status = fs::read_to_string("/proc/self/status").ok()?;
+    let ppid = status
+        .lines()
+        .find_map(|line| line.strip_prefix("PPid:"))?
+        .trim();
+    let parent = fs::read_to_string(format!("/proc/{ppid}/comm")).ok()?;
+    normalize_shell(parent.trim())
+        .filter(|shell| KNOWN_SHELLS.contains(shell))
+        .map(Into::into)
+}
+
+/// Turns `/usr/bin/zsh` into `zsh`, and `-bash` (how login shells are named) into `bash`
+fn normalize_shell(shell: &str) -> Option<&str> {
+    let name = shell.rsplit('/').next()?.trim_start_matches('-');
+    (!name.is_empty()).then_some(name)
+}
+
+/// Detects the desktop environment from the session environment. If the environment doesn't tell
+/// us (e.g. we were started over ssh or from a tty), a running compositor or shell of a known
+/// desktop still does
+pub fn detect_desktop_environment() -> Option<DesktopEnvironment> {
+    let var = |name| {
+        env::var(name)
+            .ok()
+            .filter(|value: &String| !value.is_empty())
+    };
+    let current_desktop = var("XDG_CURRENT_DESKTOP");
+    let session = var("DESKTOP_SESSION");
+    if let Some(desktop) = desktop_from_env(current_desktop.as_deref(), session.as_deref()) {
+        return Some(desktop);
+    }
+    running_processes()
+        .iter()
+        .find_map(|name| desktop_from_process(name))
+}
+
+fn desktop_from_env(
+    current_desktop: Option<&str>,
+    session: Option<&str>,
+) -> Option<DesktopEnvironment> {
+    // A list like `ubuntu:GNOME`, the most specific desktop first
+    let desktops = current_desktop
+        .into_iter()
+        .flat_map(|desktop| desktop.split(':'));
+    // Sessions are named after the desktop file, like `plasmawayland` or `/usr/share/xsessions/i3`
+    let session = session.and_then(|session| session.rsplit('/').next());
+    let recognized = desktops.chain(session).find_map(|name| {
+        let desktop = match name.to_lowercase().as_str() {
+            "gnome" | "gnome-classic" | "gnome-xorg" | "ubuntu" | "pop" => {
+                DesktopEnvironment::Gnome
+            }
+            "kde" | "plasma" | "plasmawayland" | "plasmax11" => DesktopEnvironment::Kde,
+            "xfce" | "xfce4" => DesktopEnvironment::Xfce,
+            "mate" => DesktopEnvironment::Mate,
+            "cinnamon" | "x-cinnamon" => DesktopEnvironment::Cinnamon,
+            "i3" => DesktopEnvironment::I3,
+            "sway" => DesktopEnvironment::Sway,
+            "hyprland" => DesktopEnvironment::Hyprland,
+            "cosmic" => DesktopEnvironment::Cosmic,
+            _ => return None,
+        };
+        Some(desktop)
+    });
+    match recognized {
+        Some(desktop) => Some(desktop),
+        None if current_desktop.is_some() || session.is_some() => Some(DesktopEnvironment::Unknown),
+        None => None,
+    }
+}
+
+/// The desktop a process with this name is the compositor or shell of
+fn desktop_from_process(name: &str) -> Option<DesktopEnvironment> {
+    let desktop = match name {
+        "gnome-shell" => DesktopEnvironment::Gnome,
+        "plasmashell" | "kwin_wayland" | "kwin_x11" => DesktopEnvironment::Kde,
+        "xfce4-session" | "xfwm4" => DesktopEnvironment::Xfce,
+        "mate-session" | "marco" => DesktopEnvironment::Mate,
+        "cinnamon" | "cinnamon-session" => DesktopEnvironment::Cinnamon,
+        "i3" => DesktopEnvironment::I3,
+        "sway" => DesktopEnvironment::Sway,
+        "Hyprland" => DesktopEnvironment::Hyprland,
+        "cosmic-comp" => DesktopEnvironment::Cosmic,
+        _ => return None,
+    };
+    Some(desktop)
+}
+
+/// The names of all the processes we can see, from `/proc/<pid>/comm`
+fn running_processes() -> Vec<Box<str>> {
+    let Ok(entries) = fs::read_dir("/proc") else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter(|entry| {
+            entry
+                .file_name()
+                .to_string_lossy()
+                .bytes()
+                .all(|byte| byte.is_ascii_digit())
+        })
+        .filter_map(|entry| fs::read_to_string(entry.path().join("comm")).ok())
+        .map(|name| name.trim().into())
+        .collect()
+}
+
+/// Detects the sound server by its process, or by its socket in the runtime directory of the user.
+/// Without either, ALSA is what's left if there are sound cards at all
+pub fn detect_audio_server() -> Option<AudioServer> {
+    let processes = running_processes();
+    let sockets: Vec<Box<str>> = get_uid()
+        .and_then(|uid| fs::read_dir(format!("/run/user/{uid}")).ok())
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.file_name().to_string_lossy().into())
+        .collect();
+    audio_server_from(&processes, &sockets, Path::new("/proc/asound").exists())
+}
+
+fn audio_server_from(
+    processes: &[Box<str>],
+    sockets: &[Box<str>],
+    has_sound_cards: bool,
+) -> Option<AudioServer> {
+    let running = |names: &[&str]| processes.iter().any(|process| names.contains(&&**process));
+    let socket = |name: &str| sockets.iter().any(|socket| &**socket == name);
+    // PipeWire comes with a PulseAudio server of its own, so it has to be checked first
+    if running(&["pipewire", "pipewire-pulse"]) || socket("pipewire-0") {
+        Some(AudioServer::PipeWire)
+    } else if running(&["pulseaudio"]) || socket("pulse") {
+        Some(AudioServer::PulseAudio)
+    } else if running(&["jackd", "jackdbus"]) {
+        Some(AudioServer::Jack)
+    } else {
+        has_sound_cards.then_some(AudioServer::Alsa)
+    }
+}
+
+/// The DRM cards, `card0`, `card1`, ..., leaving out their connectors like `card0-DP-1`
+fn drm_cards() -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir("/sys/class/drm") else {
+        return Vec::new();
+    };
+    let mut cards: Vec<PathBuf> = entries
+        .flatten()
+        .filter(|entry| {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            name.strip_prefix("card")
+                .is_some_and(|num| !num.is_empty() && num.bytes().all(|byte| byte.is_ascii_digit()))
+        })
+        .map(|entry| entry.path())
+        .collect();
+    cards.sort();
+    cards
+}
+
+/// The vendor of the PCI device the card belongs to
+fn card_vendor(card: &Path) -> Option<GpuVendor> {
+    let vendor = fs::read_to_string(card.join("device/vendor")).ok()?;
+    Some(gpu_vendor_from_pci_id(&vendor))
+}
+
+/// The vendors of every GPU, in the order of their cards. A system with two GPUs by the same
+/// vendor has it twice
+pub fn detect_gpu_vendors() -> Vec<GpuVendor> {
+    drm_cards()
+        .iter()
+        .filter_map(|card| card_vendor(card))
+        .collect()
+}
+
+/// The vendor of the GPU the firmware used for booting, or of the first GPU if none of them says
+/// so
+fn detect_primary_gpu_vendor() -> Option<GpuVendor> {
+    let cards = drm_cards();
+    let boot_vga = cards.iter().find(|card| {
+        fs::read_to_string(card.join("device/boot_vga")).is_ok_and(|boot| boot.trim() == "1")
+    });
+    boot_vga
+        .into_iter()
+        .chain(&cards)
+        .find_map(|card| card_vendor(card))
+}
+
+/// PCI vendor ids look like `0x10de`
+fn gpu_vendor_from_pci_id(id: &str) -> GpuVendor {
+    match id.trim().to_lowercase().as_str() {
+        "0x10de" => GpuVendor::Nvidia,
+        "0x1002" => GpuVendor::Amd,
+        "0x8086" => GpuVendor::Intel,
+        _ => GpuVendor::Unknown,
+    }
+}
+
+/// UEFI firmware shows up in `/sys/firmware/efi`, the kernel only creates it when it was booted
+/// by UEFI
+pub fn detect_boot_mode() -> BootMode {
+    boot_mode_from(Path::new("/sys/firmware"))
+}
+
+fn boot_mode_from(firmware: &Path) -> BootMode {
+    if firmware.join("efi").is_dir() {
+        BootMode::Uefi
+    } else if firmware.is_dir() {
+        BootMode::Bios
+    } else {
+        BootMode::Unknown
+    }
+}
+
+/// Looks for a TPM 2.0 in sysfs. 1.2 chips are left out, systemd-cryptenroll and clevis need 2.0
+pub fn detect_tpm() -> bool {
+    tpm_from(Path::new("/sys/class/tpm"))
+}
+
+fn tpm_from(tpm_class: &Path) -> bool {
+    fs::read_to_string(tpm_class.join("tpm0/tpm_version_major"))
+        .is_ok_and(|version| version.trim() == "2")
+}
+
+/// Looks for a battery in `/sys/class/power_supply`, and reads how full it is. Desktops have
+/// none, and get `(false, None)`
+pub fn get_battery_info() -> (bool, Option<u8>) {
+    battery_info_from(Path::new("/sys/class/power_supply"))
+}
+
+/// Batteries are `BAT0` on most laptops, but some start counting at 1, or have a second one. The
+/// first that's present is used
+fn battery_info_from(power_supply: &Path) -> (bool, Option<u8>) {
+    let battery = (0..4)
+        .map(|number| power_supply.join(format!("BAT{number}")))
+        .find(|battery| {
+            fs::read_to_string(battery.join("present")).is_ok_and(|present| present.trim() == "1")
+        });
+    let Some(battery) = battery else {
+        return (false, None);
+    };
+    let percent = fs::read_to_string(battery.join("capacity"))
+        .ok()
+        .and_then(|capacity| capacity.trim().parse::<u8>().ok())
+        .filter(|&percent| percent <= 100);
+    (true, percent)
+}
+
+/// Looks for a Bluetooth adapter in `/sys/class/bluetooth`
+pub fn detect_bluetooth() -> bool {
+    bluetooth_from(Path::new("/sys/class/bluetooth"))
+}
+
+/// Adapters are `hci0`, `hci1`, and so on. Connections to devices show up next to them, but only
+/// while there's an adapter
+fn bluetooth_from(bluetooth_class: &Path) -> bool {
+    fs::read_dir(bluetooth_class).is_ok_and(|mut entries| {
+        entries.any(|entry| {
+            entry.is_ok_and(|entry| entry.file_name().to_string_lossy().starts_with("hci"))
+        })
+    })
+}
+
+/// Looks for a wireless network interface in `/sys/class/net`
+pub fn detect_wifi() -> bool {
+    !get_wifi_interfaces().is_empty()
+}
+
+/// The wireless network interfaces in `/sys/class/net`, sorted by name
+pub fn get_wifi_interfaces() -> Vec<Box<str>> {
+    wifi_interfaces_in(Path::new("/sys/class/net"))
+}
+
+/// Only wireless interfaces have a `wireless` directory, whatever they're named
+fn wifi_interfaces_in(net_class: &Path) -> Vec<Box<str>> {
+    let Ok(entries) = fs::read_dir(net_class) else {
+        return Vec::new();
+    };
+    let mut interfaces: Vec<Box<str>> = entries
+        .filter_map(Result::ok)
+        .filter(|entry| entry.path().join("wireless").is_dir())
+        .map(|entry| entry.file_name().to_string_lossy().into())
+        .collect();
+    interfaces.sort();
+    interfaces
+}
+
+/// Reads the filesystem type of `/` from `/proc/mounts`
+pub fn get_root_filesystem() -> Option<Box<str>> {
+    let contents = fs::read_to_string("/proc/mounts").ok()?;
+    parse_root_filesystem(&contents)
+}
+
+/// Each line of `/proc/mounts` is `device mountpoint type options dump pass`. Something can be
+/// mounted over `/` again, like an overlay in live systems, and the last mount is what's seen
+fn parse_root_filesystem(contents: &str) -> Option<Box<str>> {
+    contents
+        .lines()
+        .rev()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace().skip(1);
+            match (fields.next(), fields.next()) {
+                (Some("/"), Some(fs_type)) => Some(fs_type),
+                _ => None,
+            }
+        })
+        // rootfs is the initramfs the kernel starts from, which stays listed under the real one
+        .find(|&fs_type| fs_type != "rootfs")
+        .map(Into::into)
+}
+
+/// Reads `SecureBoot` and `SetupMode` from the EFI variables, `None` if the system didn't boot
+/// with UEFI, or efivarfs isn't mounted
+///
+/// With Secure Boot enabled, the kernel is usually in lockdown mode: it only loads signed kernel
+/// modules. Modules built on the system, like the NVIDIA driver through DKMS, or VirtualBox, then
+/// have to be signed with a key that's enrolled with mokutil, or they fail to load. Bootloaders
+/// have to be signed too, or come with shim
+pub fn detect_secure_boot() -> Option<SecureBootStatus> {
+    secure_boot_from(Path::new("/sys/firmware/efi/efivars"))
+}
+
+fn secure_boot_from(efivars: &Path) -> Option<SecureBootStatus> {
+    let entries: Vec<_> = fs::read_dir(efivars)
+        .ok()?
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .collect();
+    // The names end with the GUID of the vendor, which is the global one for both of them
+    let is_set = |name: &str| {
+        entries
+            .iter()
+            .find(|path| {
+                path.file_name()
+                    .and_then(|file| file.to_str())
+                    .is_some_and(|file| file.starts_with(name))
+            })
+            .is_some_and(|path| efivar_is_set(path))
+    };
+    Some(SecureBootStatus {
+        enabled: is_set("SecureBoot-"),
+        setup_mode: is_set("SetupMode-"),
+    })
+}
+
+/// An EFI variable is 4 bytes of attributes, then the value. These ones are a single byte, 1 when
+/// they're set
+fn efivar_is_set(path: &Path) -> bool {
+    fs::read(path).is_ok_and(|contents| contents.get(4) == Some(&1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_dir::TestDir;
+
+    #[test]
+    fn lsb_release_is_translated() {
+        let lsb = parse_os_release(
+            "DISTRIB_ID=Ubuntu\nDISTRIB_RELEASE=14.04\nDISTRIB_DESCRIPTION=\"Ubuntu 14.04.6 LTS\"\n",
+        );
+        let os_info = lsb_to_os_release(lsb);
+        assert_eq!(os_info.get("id").map(AsRef::as_ref), Some("ubuntu"));
+        assert_eq!(
+            os_info.get("pretty_name").map(AsRef::as_ref),
+            Some("Ubuntu 14.04.6 LTS")
+        );
+        assert_eq!(os_info.get("version_id").map(AsRef::as_ref), Some("14.04"));
+    }
+
+    #[test]
+    fn first_existing_os_release_is_located() {
+        let dir = TestDir::new("os-release");
+        let missing = dir.join("os-release");
+        let fallback = dir.join("lsb-release");
+        fs::write(&fallback, "DISTRIB_ID=Ubuntu\n").unwrap();
+
+        assert_eq!(
+            first_existing(&[&missing, &fallback]),
+            Some(fallback.clone())
+        );
+        assert_eq!(first_existing(&[&missing]), None);
+    }
+
+    #[test]
+    fn derivatives_fall_back_to_id_like() {
+        let binary_name = |id, id_like: &[&str]| {
+            let id_like: Vec<Box<str>> = id_like.iter().map(|&id| id.into()).collect();
+            get_package_manager(id, &id_like).map(|pm| pm.binary_name())
+        };
+        assert_eq!(binary_name("pop", &["ubuntu", "debian"]), Some("apt-get"));
+        assert_eq!(binary_name("voidish", &["void"]), Some("xbps-install"));
+        assert_eq!(binary_name("arch", &["debian"]), Some("pacman"));
+    }
+
+    #[test]
+    fn os_release_values_are_unquoted() {
+        let os_info = parse_os_release("NAME=\"Void\"\nID=void\nPRETTY_NAME='Void Linux'\n");
+        assert_eq!(os_info.get("id").map(AsRef::as_ref), Some("void"));
+        assert_eq!(
+            os_info.get("pretty_name").map(AsRef::as_ref),
+            Some("Void Linux")
+        );
+    }
+
+    #[test]
+    fn os_release_fields_are_typed() {
+        let os_release = OsRelease::from(parse_os_release(
+            "NAME=\"Pop!_OS\"\nID=pop\nID_LIKE=\"ubuntu debian\"\nVERSION_ID=\"22.04\"\n\
+             BUG_REPORT_URL=\"https://github.com/pop-os/pop/issues\"\n",
+        ));
+        assert_eq!(&*os_release.id, "pop");
+        assert_eq!(os_release.id_like, vec!["ubuntu".into(), "debian".into()]);
+        assert_eq!(os_release.version_id.as_deref(), Some("22.04"));
+        assert_eq!(
+            os_release.bug_report_url.as_deref(),
+            Some("https://github.com/pop-os/pop/issues")
+        );
+        assert_eq!(os_release.variant_id, None);
+        assert_eq!(
+            os_release.extra,
+            HashMap::from([("name".into(), "Pop!_OS".into())])
+        );
+    }
+
+    #[cfg(feature = "semver")]
+    #[test]
+    fn version_id_is_padded() {
+        use semver::Version;
+
+        let version = |version_id: &str| {
+            OsRelease {
+                version_id: Some(version_id.into()),
+                ..OsRelease::default()
+            }
+            .version_id_semver()
+        };
+        assert_eq!(version("22.04"), Some(Version::new(22, 4, 0)));
+        assert_eq!(version("38"), Some(Version::new(38, 0, 0)));
+        assert_eq!(version("7.9.2009"), Some(Version::new(7, 9, 2009)));
+        assert!(version("22.04") >= version("20.10"));
+        assert_eq!(version("1.2.3.4"), None);
+        assert_eq!(version("bookworm"), None);
+        assert_eq!(version("40-beta"), None);
+        assert_eq!(version(""), None);
+        assert_eq!(OsRelease::default().version_id_semver(), None);
+    }
+
+    #[test]
+    fn commands_are_looked_up_in_path() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = TestDir::new("commands");
+        let bin = dir.join("bin");
+        let local_bin = dir.join("local/bin");
+        fs::create_dir_all(&bin).unwrap();
+        fs::create_dir_all(local_bin.join("git")).unwrap();
+        for (file, mode) in [(bin.join("git"), 0o755), (local_bin.join("curl"), 0o644)] {
+            fs::write(&file, "#!/bin/sh\n").unwrap();
+            fs::set_permissions(&file, fs::Permissions::from_mode(mode)).unwrap();
+        }
+
+        // The directory named git that comes first doesn't count
+        let path = std::env::join_paths([&local_bin, &bin]).unwrap();
+        assert!(has_command_in("git", &path));
+        assert!(!has_command_in("curl", &path));
+        assert!(!has_command_in("", &path));
+        assert!(!has_command_in("git", std::ffi::OsStr::new("")));
+        assert!(has_command_in(bin.join("git").to_str().unwrap(), &path));
+        assert!(!has_command_in("bin/git", std::ffi::OsStr::new("")));
+    }
+
+    #[test]
+    fn kernel_version_is_parsed() {
+        let version = "Linux version 6.9.7-arch1-1 (linux@archlinux) (gcc (GCC) 14.1.1) #1 SMP PREEMPT_DYNAMIC Fri, 28 Jun 2024 04:32:50 +0000\n";
+        assert_eq!(
+            parse_kernel_version(version).as_deref(),
+            Some("6.9.7-arch1-1")
+        );
+        assert_eq!(parse_kernel_version("garbage"), None);
+    }
+
+    const X86_64_CPUINFO: &str = "\
+processor\t: 0
+vendor_id\t: AuthenticAMD
+model name\t: AMD Ryzen 7 5800X 8-Core Processor
+cpu MHz\t\t: 3800.000
+
+processor\t: 1
+vendor_id\t: AuthenticAMD
+model name\t: AMD Ryzen 7 5800X 8-Core Processor
+cpu MHz\t\t: 3800.000
+";
+
+    const AARCH64_CPUINFO: &str = "\
+processor\t: 0
+BogoMIPS\t: 108.00
+Features\t: fp asimd evtstrm crc32 cpuid
+CPU implementer\t: 0x41
+CPU part\t: 0xd08
+
+processor\t: 1
+BogoMIPS\t: 108.00
+Features\t: fp asimd evtstrm crc32 cpuid
+CPU implementer\t: 0x41
+CPU part\t: 0xd08
+
+processor\t: 2
+processor\t: 3
+
+Revision\t: c03111
+Serial\t\t: 10000000a7b8c3d4
+Model\t\t: Raspberry Pi 4 Model B Rev 1.1
+";
+
+    #[test]
+    fn cpu_info_is_parsed_on_x86_64() {
+        let (model, count) = parse_cpu_info(X86_64_CPUINFO);
+        assert_eq!(model.as_deref(), Some("AMD Ryzen 7 5800X 8-Core Processor"));
+        assert_eq!(count, Some(2));
+    }
+
+    #[test]
+    fn cpu_info_is_parsed_on_aarch64() {
+        let (model, count) = parse_cpu_info(AARCH64_CPUINFO);
+        assert_eq!(model.as_deref(), Some("Raspberry Pi 4 Model B Rev 1.1"));
+        assert_eq!(count, Some(4));
+    }
+
+    const MEMINFO: &str = "\
+MemTotal:       32791336 kB
+MemFree:        20109496 kB
+MemAvailable:   27372960 kB
+Buffers:          223756 kB
+";
+
+    #[test]
+    fn total_memory_is_parsed() {
+        assert_eq!(
+            parse_meminfo_field(MEMINFO, "MemTotal").map(|kib| kib / 1024),
+            Some(32022)
+        );
+        assert_eq!(parse_meminfo_field(MEMINFO, "SwapTotal"), None);
+        assert_eq!(parse_meminfo_field("MemTotal: lots kB", "MemTotal"), None);
+    }
+
+    #[test]
+    fn architecture_names_are_normalized() {
+        assert_eq!(normalize_architecture("amd64"), Some("x86_64"));
+        assert_eq!(normalize_architecture("arm64"), Some("aarch64"));
+        assert_eq!(normalize_architecture("i686"), Some("x86"));
+        assert_eq!(normalize_architecture("loong64"), Some("loongarch64"));
+        assert_eq!(normalize_architecture("pdp11"), None);
+    }
+
+    #[test]
+    fn architecture_is_found_in_kernel_release() {
+        let version = "Linux version 6.8.5-301.fc40.x86_64 (mockbuild@fedoraproject.org) #1 SMP";
+        assert_eq!(find_architecture(version), Some("x86_64"));
+        let version = "Linux version 6.9.7-arch1-1 (linux@archlinux) #1 SMP";
+        assert_eq!(find_architecture(version), None);
+    }
+
+    #[test]
+    fn init_version_is_recognized() {
+        assert_eq!(
+            parse_init_version("systemd 256 (256.1-1-arch)\n+PAM +AUDIT"),
+            InitSystem::Systemd
+        );
+        assert_eq!(
+            parse_init_version("SysV init version: 3.09\n"),
+            InitSystem::SysVinit
+        );
+        assert_eq!(
+            parse_init_version("init: unknown option"),
+            InitSystem::Unknown
+        );
+    }
+
+    #[test]
+    fn display_server_is_detected_from_env() {
+        assert_eq!(
+            display_server_from_env(Some("wayland-1"), None, Some(":0"), Some("wayland")),
+            Some(DisplayServer::Wayland)
+        );
+        assert_eq!(
+            display_server_from_env(None, None, Some(":0"), None),
+            Some(DisplayServer::X11)
+        );
+        assert_eq!(
+            display_server_from_env(None, Some("/run/mir_socket"), None, None),
+            Some(DisplayServer::Mir)
+        );
+        assert_eq!(
+            display_server_from_env(None, None, None, Some("surfaceflinger")),
+            Some(DisplayServer::Unknown)
+        );
+        assert_eq!(display_server_from_env(None, None, None, Some("tty")), None);
+    }
+
+    fn mock_system() -> System {
+        System::from(Detected {
+            os_release: OsRelease {
+                id: "arch".into(),
+                pretty_name: "Arch Linux".into(),
+                build_id: Some("rolling".into()),
+                home_url: Some("https://archlinux.org/".into()),
+                ..OsRelease::default()
+            },
+            package_manager: Some(PackageManager::Pacman),
+            kernel_version: Some("6.9.7-arch1-1".into()),
+            cpu_model: Some("AMD Ryzen 7 5800X 8-Core Processor".into()),
+            cpu_count: Some(16),
+            total_memory_mb: Some(32022),
+            architecture: Some("x86_64".into()),
+            init_system: Some(InitSystem::Systemd),
+            display_server: None,
+            uptime_seconds: Some(350735),
+            virt_env: Some(VirtEnv::None),
+            hostname: "archlinux".into(),
+            current_user: "chris".into(),
+            is_root: false,
+            current_shell: Some("zsh".into()),
+            desktop_environment: Some(DesktopEnvironment::Kde),
+            audio_server: Some(AudioServer::PipeWire),
+            gpu_vendor: Some(GpuVendor::Amd),
+            swap_total_mb: Some(0),
+            swap_free_mb: Some(0),
+            load_average: Some((0.52, 0.58, 0.59)),
+            machine_id: Some("b08dfa6083e7567a1921a715000001fb".into()),
+            boot_mode: BootMode::Uefi,
+            secure_boot: Some(SecureBootStatus {
+                enabled: true,
+                setup_mode: false,
+            }),
+            root_filesystem: Some("btrfs".into()),
+            tpm_available: true,
+            battery_present: true,
+            battery_percent: Some(80),
+            bluetooth_present: false,
+            wifi_interfaces: vec!["wlan0".into()],
+            locale: Some("en_US.UTF-8".into()),
+            timezone: Some("Europe/Berlin".into()),
+            additional_package_managers: vec![PackageManager::Flatpak],
+            package_manager_locked: false,
+        })
+    }
+
+    #[test]
+    fn system_is_displayed() {
+        assert_eq!(
+            mock_system().to_string(),
+            "\
+Distribution: Arch Linux (arch)
+Package manager: pacman
+Kernel: 6.9.7-arch1-1
+Architecture: x86_64
+CPU: AMD Ryzen 7 5800X 8-Core Processor (16 threads)
+Memory: 32022 MiB
+Init system: systemd
+Display server: none"
+        );
+    }
+
+    #[test]
+    fn container_is_detected_from_environ() {
+        let environ = b"PATH=/usr/bin\0container=podman\0TERM=xterm\0";
+        assert_eq!(virt_from_environ(environ), Some(VirtEnv::Podman));
+        assert_eq!(virt_from_environ(b"container=lxc\0"), Some(VirtEnv::Lxc));
+        assert_eq!(virt_from_environ(b"PATH=/usr/bin\0"), None);
+    }
+
+    #[test]
+    fn hypervisor_is_detected_from_dmi() {
+        assert_eq!(
+            virt_from_dmi("QEMU\nStandard PC (Q35 + ICH9, 2009)\n"),
+            Some(VirtEnv::Qemu)
+        );
+        assert_eq!(
+            virt_from_dmi("innotek GmbH\nVirtualBox\n"),
+            Some(VirtEnv::VirtualBox)
+        );
+        assert_eq!(virt_from_dmi("LENOVO\n20XW\n"), None);
+    }
+
+    #[test]
+    fn hypervisor_flag_is_found() {
+        assert!(has_hypervisor_flag(
+            "flags\t\t: fpu vme de pse hypervisor lahf_lm\n"
+        ));
+        assert!(!has_hypervisor_flag(X86_64_CPUINFO));
+    }
+
+    #[test]
+    fn hostname_is_read_from_file() {
+        let dir = TestDir::new("hostname");
+        let path = dir.join("hostname");
+        fs::write(&path, "  workstation\n").unwrap();
+        assert_eq!(read_hostname(&path).as_deref(), Some("workstation"));
+
+        fs::write(&path, "\n").unwrap();
+        assert_eq!(read_hostname(&path), None);
+
+        fs::remove_file(&path).unwrap();
+        assert_eq!(read_hostname(&path), None);
+    }
+
+    #[test]
+    fn user_is_found_in_passwd() {
+        let passwd = "\
+root:x:0:0::/root:/bin/bash
+nobody:x:65534:65534:Kernel Overflow User:/:/usr/bin/nologin
+chris:x:1000:1000::/home/chris:/bin/zsh
+";
+        assert_eq!(user_from_passwd(passwd, 1000).as_deref(), Some("chris"));
+        assert_eq!(user_from_passwd(passwd, 0).as_deref(), Some("root"));
+        assert_eq!(user_from_passwd(passwd, 1001), None);
+    }
+
+    #[test]
+    fn current_user_comes_from_env() {
+        assert_eq!(
+            user_from_env(Some("linutil-test".to_string())).as_deref(),
+            Some("linutil-test")
+        );
+        assert_eq!(user_from_env(Some(String::new())), None);
+        assert_eq!(user_from_env(None), None);
+    }
+
+    #[test]
+    fn locale_comes_from_env_then_locale_conf() {
+        let lang = Some("en_US.UTF-8".to_string());
+        assert_eq!(
+            locale_from(Some("de_DE.UTF-8".to_string()), lang.clone()).as_deref(),
+            Some("de_DE.UTF-8")
+        );
+        assert_eq!(
+            locale_from(Some(String::new()), lang).as_deref(),
+            Some("en_US.UTF-8")
+        );
+        assert_eq!(locale_from(None, Some(String::new())), None);
+
+        let dir = TestDir::new("locale");
+        let locale_conf = dir.join("locale.conf");
+        fs::write(&locale_conf, "LC_TIME=en_GB.UTF-8\nLANG=\"fr_FR.UTF-8\"\n").unwrap();
+        assert_eq!(
+            parse_locale_file(&locale_conf).as_deref(),
+            Some("fr_FR.UTF-8")
+        );
+        fs::write(&locale_conf, "LC_TIME=en_GB.UTF-8\n").unwrap();
+        assert_eq!(parse_locale_file(&locale_conf), None);
+    }
+
+    #[test]
+    fn timezone_comes_from_env_then_files() {
+        assert_eq!(
+            timezone_from_env(Some(":America/New_York".to_string())).as_deref(),
+            Some("America/New_York")
+        );
+        assert_eq!(timezone_from_env(Some(":".to_string())), None);
+        assert_eq!(timezone_from_env(None), None);
+
+        let etc = TestDir::new("timezone");
+        fs::write(etc.join("timezone"), "Europe/Berlin\n").unwrap();
+        assert_eq!(
+            read_timezone_file(&etc.join("timezone")).as_deref(),
+            Some("Europe/Berlin")
+        );
+        std::os::unix::fs::symlink("../usr/share/zoneinfo/Asia/Tokyo", etc.join("localtime"))
+            .unwrap();
+        assert_eq!(
+            timezone_from_link(&etc.join("localtime")).as_deref(),
+            Some("Asia/Tokyo")
+        );
+        assert_eq!(timezone_from_link(&etc.join("timezone")), None);
+    }
+
+    #[test]
+    fn shell_names_are_normalized() {
+        assert_eq!(normalize_shell("/usr/bin/zsh"), Some("zsh"));
+        assert_eq!(normalize_shell("-bash"), Some("bash"));
+        assert_eq!(normalize_shell("fish"), Some("fish"));
+        assert_eq!(normalize_shell("/bin/"), None);
+    }
+
+    #[test]
+    fn desktop_is_detected_from_env() {
+        assert_eq!(
+            desktop_from_env(Some("ubuntu:GNOME"), Some("ubuntu")),
+            Some(DesktopEnvironment::Gnome)
+        );
+        assert_eq!(
+            desktop_from_env(None, Some("/usr/share/xsessions/i3")),
+            Some(DesktopEnvironment::I3)
+        );
+        assert_eq!(
+            desktop_from_env(Some("KDE"), None),
+            Some(DesktopEnvironment::Kde)
+        );
+        assert_eq!(
+            desktop_from_env(Some("Enlightenment"), None),
+            Some(DesktopEnvironment::Unknown)
+        );
+        assert_eq!(desktop_from_env(None, None), None);
+    }
+
+    #[test]
+    fn desktop_is_detected_from_processes() {
+        assert_eq!(
+            desktop_from_process("Hyprland"),
+            Some(DesktopEnvironment::Hyprland)
+        );
+        assert_eq!(
+            desktop_from_process("kwin_wayland"),
+            Some(DesktopEnvironment::Kde)
+        );
+        assert_eq!(desktop_from_process("bash"), None);
+    }
+
+    #[test]
+    fn audio_server_is_detected() {
+        let names =
+            |names: &[&str]| -> Vec<Box<str>> { names.iter().map(|&name| name.into()).collect() };
+        assert_eq!(
+            audio_server_from(
+                &names(&["pipewire-pulse", "systemd"]),
+                &names(&["pulse"]),
+                true
+            ),
+            Some(AudioServer::PipeWire)
+        );
+        assert_eq!(
+            audio_server_from(&[], &names(&["pipewire-0", "pulse"]), true),
+            Some(AudioServer::PipeWire)
+        );
+        assert_eq!(
+            audio_server_from(&names(&["pulseaudio"]), &[], true),
+            Some(AudioServer::PulseAudio)
+        );
+        assert_eq!(
+            audio_server_from(&names(&["jackd"]), &[], true),
+            Some(AudioServer::Jack)
+        );
+        assert_eq!(audio_server_from(&[], &[], true), Some(AudioServer::Alsa));
+        assert_eq!(audio_server_from(&[], &[], false), None);
+    }
+
+    #[test]
+    fn gpu_vendor_is_recognized() {
+        assert_eq!(gpu_vendor_from_pci_id("0x10de\n"), GpuVendor::Nvidia);
+        assert_eq!(gpu_vendor_from_pci_id("0x1002"), GpuVendor::Amd);
+        assert_eq!(gpu_vendor_from_pci_id("0x8086"), GpuVendor::Intel);
+        // QEMU's virtual GPU
+        assert_eq!(gpu_vendor_from_pci_id("0x1234"), GpuVendor::Unknown);
+    }
+
+    #[test]
+    fn swap_is_parsed() {
+        let meminfo = "\
+MemTotal:       32791336 kB
+SwapCached:            0 kB
+SwapTotal:       8388604 kB
+SwapFree:        8126460 kB
+";
+        assert_eq!(parse_swap_info(meminfo), (Some(8191), Some(7935)));
+        // No swap configured
+        assert_eq!(
+            parse_swap_info("SwapTotal:             0 kB\nSwapFree:              0 kB\n"),
+            (Some(0), Some(0))
+        );
+        assert_eq!(parse_swap_info(MEMINFO), (None, None));
+    }
+
+    #[test]
+    fn load_average_is_parsed() {
+        assert_eq!(
+            parse_load_average("0.52 0.58 0.59 1/1141 48213\n"),
+            Some((0.52, 0.58, 0.59))
+        );
+        assert_eq!(parse_load_average("0.52 0.58"), None);
+        assert_eq!(parse_load_average("0.52 high 0.59"), None);
+    }
+
+    #[test]
+    fn machine_id_is_read_from_file() {
+        let dir = TestDir::new("machine-id");
+        let path = dir.join("machine-id");
+        fs::write(&path, "b08dfa6083e7567a1921a715000001fb\n").unwrap();
+        assert_eq!(
+            read_machine_id(&path).as_deref(),
+            Some("b08dfa6083e7567a1921a715000001fb")
+        );
+
+        fs::write(&path, "uninitialized\n").unwrap();
+        assert_eq!(read_machine_id(&path), None);
+
+        fs::remove_file(&path).unwrap();
+        assert_eq!(read_machine_id(&path), None);
+    }
+
+    #[test]
+    fn boot_mode_is_detected() {
+        let dir = TestDir::new("firmware");
+        let firmware = dir.join("firmware");
+        assert_eq!(boot_mode_from(&firmware), BootMode::Unknown);
+
+        fs::create_dir(&firmware).unwrap();
+        assert_eq!(boot_mode_from(&firmware), BootMode::Bios);
+
+        fs::create_dir(firmware.join("efi")).unwrap();
+        assert_eq!(boot_mode_from(&firmware), BootMode::Uefi);
+    }
+
+    #[test]
+    fn tpm_is_detected() {
+        let tpm_class = TestDir::new("tpm");
+        assert!(!tpm_from(&tpm_class));
+
+        fs::create_dir_all(tpm_class.join("tpm0")).unwrap();
+        fs::write(tpm_class.join("tpm0/tpm_version_major"), "1\n").unwrap();
+        assert!(!tpm_from(&tpm_class));
+
+        fs::write(tpm_class.join("tpm0/tpm_version_major"), "2\n").unwrap();
+        assert!(tpm_from(&tpm_class));
+    }
+
+    #[test]
+    fn battery_is_detected() {
+        let power_supply = TestDir::new("power-supply");
+        fs::create_dir_all(power_supply.join("AC")).unwrap();
+        assert_eq!(battery_info_from(&power_supply), (false, None));
+
+        fs::create_dir(power_supply.join("BAT1")).unwrap();
+        fs::write(power_supply.join("BAT1/present"), "1\n").unwrap();
+        assert_eq!(battery_info_from(&power_supply), (true, None));
+
+        fs::write(power_supply.join("BAT1/capacity"), "57\n").unwrap();
+        assert_eq!(battery_info_from(&power_supply), (true, Some(57)));
+    }
+
+    #[test]
+    fn bluetooth_is_detected() {
+        let dir = TestDir::new("bluetooth");
+        let bluetooth_class = dir.join("bluetooth");
+        assert!(!bluetooth_from(&bluetooth_class));
+
+        fs::create_dir(&bluetooth_class).unwrap();
+        assert!(!bluetooth_from(&bluetooth_class));
+
+        fs::create_dir(bluetooth_class.join("hci0")).unwrap();
+        assert!(bluetooth_from(&bluetooth_class));
+    }
+
+    #[test]
+    fn wifi_interfaces_are_detected() {
+        let net_class = TestDir::new("net");
+        assert!(wifi_interfaces_in(&net_class).is_empty());
+
+        fs::create_dir_all(net_class.join("lo")).unwrap();
+        fs::create_dir_all(net_class.join("enp4s0")).unwrap();
+        fs::create_dir_all(net_class.join("wlp3s0/wireless")).unwrap();
+        assert_eq!(wifi_interfaces_in(&net_class), vec!["wlp3s0".into()]);
+    }
+
+    #[test]
+    fn root_filesystem_is_the_last_mount_on_root() {
+        let mounts = "rootfs / rootfs rw 0 0\n\
+                      /dev/nvme0n1p2 / btrfs rw,relatime,compress=zstd:3,subvol=/@ 0 0\n\
+                      proc /proc proc rw,nosuid,nodev,noexec,relatime 0 0\n\
+                      /dev/nvme0n1p1 /boot vfat rw,relatime 0 0\n";
+        assert_eq!(parse_root_filesystem(mounts).as_deref(), Some("btrfs"));
+        assert_eq!(parse_root_filesystem("/dev/sda1 /home ext4 rw 0 0\n"), None);
+    }
+
+    #[test]
+    fn secure_boot_is_read_from_efivars() {
+        let dir = TestDir::new("efivars");
+        let efivars = dir.join("efivars");
+        assert_eq!(secure_boot_from(&efivars), None);
+
+        fs::create_dir(&efivars).unwrap();
+        let guid = "8be4df61-93ca-11d2-aa0d-00e098032b8c";
+        fs::write(efivars.join(format!("SecureBoot-{guid}")), [6, 0, 0, 0, 1]).unwrap();
+        fs::write(efivars.join(format!("SetupMode-{guid}")), [6, 0, 0, 0, 0]).unwrap();
+        assert_eq!(
+            secure_boot_from(&efivars),
+            Some(SecureBootStatus {
+                enabled: true,
+                setup_mode: false,
+            })
+        );
+
+        fs::remove_file(efivars.join(format!("SecureBoot-{guid}"))).unwrap();
+        assert_eq!(
+            secure_boot_from(&efivars).map(|status| status.enabled),
+            Some(false)
+        );
+    }
+
+    #[test]
+    fn distro_family_follows_id_like() {
+        let mut system = mock_system();
+        assert_eq!(system.distro_family(), Some(DistroFamily::Arch));
+
+        system.os_release.id = "pop".into();
+        system.os_release.id_like = vec!["ubuntu".into(), "debian".into()];
+        assert_eq!(system.distro_family(), Some(DistroFamily::Debian));
+
+        system.os_release.id = "opensuse-tumbleweed".into();
+        system.os_release.id_like = vec!["opensuse".into(), "suse".into()];
+        assert_eq!(system.distro_family(), Some(DistroFamily::Rpm));
+
+        system.os_release.id = "nixos".into();
+        system.os_release.id_like = Vec::new();
+        assert_eq!(system.distro_family(), Some(DistroFamily::Unknown));
+
+        system.os_release.id = "".into();
+        assert_eq!(system.distro_family(), None);
+    }
+
+    #[test]
+    fn debug_detects_every_field() {
+        let debug = format!("{:?}", mock_system());
+        assert!(debug.contains(r#"kernel_version: Some("6.9.7-arch1-1")"#));
+        let Ok(system) = System::info() else {
+            return;
+        };
+        let debug = format!("{system:?}");
+        assert!(debug.contains(&format!("id: {:?}", system.id())));
+        assert!(!debug.contains("uninit"));
+    }
+
+    #[test]
+    fn fields_are_detected_on_first_use() {
+        let Ok(system) = System::info() else {
+            return;
+        };
+        assert!(system.kernel_version.get().is_none());
+        assert!(system.memory.get().is_none());
+        system.kernel_version();
+        system.swap_free_mb();
+        assert!(system.kernel_version.get().is_some());
+        assert!(system.memory.get().is_some());
+        assert!(system.cpu.get().is_none());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn system_is_serialized_with_every_field() {
+        let json = serde_json::to_value(mock_system()).unwrap();
+        assert_eq!(json["cpu_model"], "AMD Ryzen 7 5800X 8-Core Processor");
+        assert_eq!(json["swap_total_mb"], 0);
+        assert_eq!(json["id"], "arch");
+        assert_eq!(json["home_url"], "https://archlinux.org/");
+
+        let system: System = serde_json::from_value(json).unwrap();
+        assert_eq!(system.to_string(), mock_system().to_string());
+    }
+
+    #[test]
+    fn refresh_detects_again() {
+        let mut system = mock_system();
+        system.os_release.id = "templeos".into();
+        if system.refresh().is_err() {
+            // Without os-release, the old values stay
+            assert_eq!(system.id(), "templeos");
+            return;
+        }
+        assert_ne!(system.id(), "templeos");
+        assert!(system.kernel_version.get().is_none());
+    }
+
+    #[test]
+    fn comparable_id_leaves_out_the_version() {
+        let mut system = mock_system();
+        assert_eq!(system.comparable_id(), "arch");
+        system.os_release.id = "ubuntu-22.04".into();
+        assert_eq!(system.comparable_id(), "ubuntu");
+        assert_eq!(system.id(), "ubuntu-22.04");
+        system.os_release.id = "MyDistro".into();
+        assert_eq!(system.comparable_id(), "mydistro");
+    }
+
+    #[test]
+    fn systems_are_equal_by_id_and_architecture() {
+        use std::collections::HashSet;
+
+        let system = mock_system();
+        let mut renamed = mock_system();
+        renamed.os_release.pretty_name = "Arch Linux (rolling)".into();
+        renamed.hostname = OnceLock::from(Box::from("other"));
+        assert_eq!(system, renamed);
+        assert_eq!(HashSet::from([system, renamed]).len(), 1);
+
+        let mut arm = mock_system();
+        arm.architecture = OnceLock::from(Some("aarch64".into()));
+        assert_ne!(mock_system(), arm);
+        let mut debian = mock_system();
+        debian.os_release.id = "debian".into();
+        assert_ne!(mock_system(), debian);
+    }
+}
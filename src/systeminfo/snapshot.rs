@@ -0,0 +1,248 @@
+use std::{
+    collections::hash_map::DefaultHasher,
+    fmt, fs,
+    hash::Hasher,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
+
+use super::{InitSystem, System};
+
+/// Configuration files that scripts are likely to change, they're hashed for every snapshot
+const WATCHED_FILES: [&str; 12] = [
+    "/etc/passwd",
+    "/etc/group",
+    "/etc/sudoers",
+    "/etc/shells",
+    "/etc/fstab",
+    "/etc/hosts",
+    "/etc/environment",
+    "/etc/os-release",
+    "/etc/default/grub",
+    "/etc/pacman.conf",
+    "/etc/apt/sources.list",
+    "/etc/dnf/dnf.conf",
+];
+
+/// What the system looked like at one point in time, to compare with what it looks like after
+/// running a script
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SystemSnapshot {
+    /// Packages installed with the native package manager, `None` if we can't count them
+    pub installed_packages: Option<usize>,
+    /// Services the init system is running, `None` if we can't count them
+    pub active_services: Option<usize>,
+    /// Each of the watched files, with a hash of its contents. `None` if it doesn't exist. The
+    /// hash tells changes apart and nothing more, it isn't cryptographic
+    pub file_hashes: Vec<(PathBuf, Option<u64>)>,
+}
+
+/// How the system changed between two snapshots. Displays as a list of the changes
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SystemDiff {
+    /// The number of installed packages before and after, if it changed
+    pub installed_packages: Option<(usize, usize)>,
+    /// The number of active services before and after, if it changed
+    pub active_services: Option<(usize, usize)>,
+    pub changed_files: Vec<(PathBuf, FileChange)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FileChange {
+    Created,
+    Modified,
+    Removed,
+}
+
+impl System {
+    /// Takes a snapshot of the packages, services and configuration files. It can take a moment, on
+    /// some distributions the packages are counted by asking the package manager
+    pub fn snapshot(&self) -> SystemSnapshot {
+        SystemSnapshot {
+            installed_packages: self
+                .package_manager()
+                .and_then(|package_manager| package_manager.installed_count()),
+            active_services: self.init_system().and_then(count_active_services),
+            file_hashes: WATCHED_FILES
+                .iter()
+                .map(|path| (PathBuf::from(path), hash_file(Path::new(path))))
+                .collect(),
+        }
+    }
+
+    /// What changed from `before` to `after`. Counts that are missing from either snapshot aren't
+    /// compared
+    pub fn diff(before: &SystemSnapshot, after: &SystemSnapshot) -> SystemDiff {
+        let changed = |before: Option<usize>, after: Option<usize>| match (before, after) {
+            (Some(before), Some(after)) if before != after => Some((before, after)),
+            _ => None,
+        };
+        let changed_files = after
+            .file_hashes
+            .iter()
+            .filter_map(|(path, after_hash)| {
+                let before_hash = before
+                    .file_hashes
+                    .iter()
+                    .find(|(before_path, _)| before_path == path)
+                    .and_then(|(_, hash)| *hash);
+                let change = match (before_hash, after_hash) {
+                    (None, Some(_)) => FileChange::Created,
+                    (Some(_), None) => FileChange::Removed,
+                    (Some(before), Some(after)) if before != *after => FileChange::Modified,
+                    _ => return None,
+                };
+                Some((path.clone(), change))
+            })
+            .collect();
+        SystemDiff {
+            installed_packages: changed(before.installed_packages, after.installed_packages),
+            active_services: changed(before.active_services, after.active_services),
+            changed_files,
+        }
+    }
+}
+
+impl SystemDiff {
+    pub fn is_empty(&self) -> bool {
+        self.installed_packages.is_none()
+            && self.active_services.is_none()
+            && self.changed_files.is_empty()
+    }
+}
+
+impl fmt::Display for SystemDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return f.write_str("Nothing changed");
+        }
+        let mut lines = Vec::new();
+        if let Some((before, after)) = self.installed_packages {
+            lines.push(format!("Installed packages: {before} -> {after}"));
+        }
+        if let Some((before, after)) = self.active_services {
+            lines.push(format!("Active services: {before} -> {after}"));
+        }
+        for (path, change) in &self.changed_files {
+            let change = match change {
+                FileChange::Created => "Created",
+                FileChange::Modified => "Modified",
+                FileChange::Removed => "Removed",
+            };
+            lines.push(format!("{change} {}", path.display()));
+        }
+        f.write_str(&lines.join("\n"))
+    }
+}
+
+/// Counts what the init system is running, if it's one that keeps track of that somewhere simple
+fn count_active_services(init_system: InitSystem) -> Option<usize> {
+    match init_system {
+        InitSystem::Systemd => {
+            let output = Command::new("systemctl")
+                .args(["list-units", "--type=service", "--state=active"])
+                .args(["--no-legend", "--plain"])
+                .stdin(Stdio::null())
+                .stderr(Stdio::null())
+                .output()
+                .ok()
+                .filter(|output| output.status.success())?;
+            Some(
+                String::from_utf8_lossy(&output.stdout)
+                    .lines()
+                    .filter(|line| !line.trim().is_empty())
+                    .count(),
+            )
+        }
+        // Started services are linked in there while they run
+        InitSystem::OpenRC => count_entries(Path::new("/run/openrc/started")),
+        // Enabled services are linked into the directory runsvdir supervises
+        InitSystem::Runit => ["/var/service", "/run/runit/service", "/etc/service"]
+            .iter()
+            .find_map(|dir| count_entries(Path::new(dir))),
+        InitSystem::S6 | InitSystem::SysVinit | InitSystem::Unknown => None,
+    }
+}
+
+fn count_entries(dir: &Path) -> Option<usize> {
+    Some(fs::read_dir(dir).ok()?.count())
+}
+
+fn hash_file(path: &Path) -> Option<u64> {
+    let contents = fs::read(path).ok()?;
+    let mut hasher = DefaultHasher::new();
+    hasher.write(&contents);
+    Some(hasher.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_dir::TestDir;
+
+    fn snapshot(installed_packages: usize, files: &[(&str, Option<u64>)]) -> SystemSnapshot {
+        SystemSnapshot {
+            installed_packages: Some(installed_packages),
+            active_services: None,
+            file_hashes: files
+                .iter()
+                .map(|(path, hash)| (PathBuf::from(path), *hash))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn snapshots_are_diffed() {
+        let before = snapshot(
+            800,
+            &[
+                ("/etc/fstab", Some(1)),
+                ("/etc/hosts", Some(2)),
+                ("/etc/shells", None),
+            ],
+        );
+        let after = snapshot(
+            803,
+            &[
+                ("/etc/fstab", Some(1)),
+                ("/etc/hosts", Some(3)),
+                ("/etc/shells", Some(4)),
+            ],
+        );
+        let diff = System::diff(&before, &after);
+        assert_eq!(diff.installed_packages, Some((800, 803)));
+        assert_eq!(diff.active_services, None);
+        assert_eq!(
+            diff.changed_files,
+            [
+                (PathBuf::from("/etc/hosts"), FileChange::Modified),
+                (PathBuf::from("/etc/shells"), FileChange::Created),
+            ]
+        );
+        assert_eq!(
+            diff.to_string(),
+            "Installed packages: 800 -> 803\nModified /etc/hosts\nCreated /etc/shells"
+        );
+
+        let unchanged = System::diff(&before, &before);
+        assert!(unchanged.is_empty());
+        assert_eq!(unchanged.to_string(), "Nothing changed");
+    }
+
+    #[test]
+    fn files_are_hashed_by_contents() {
+        let dir = TestDir::new("hash");
+        let path = dir.join("file");
+        fs::write(&path, "one").unwrap();
+        let first = hash_file(&path);
+        fs::write(&path, "two").unwrap();
+        assert_ne!(hash_file(&path), first);
+        assert!(first.is_some());
+
+        fs::remove_file(&path).unwrap();
+        assert_eq!(hash_file(&path), None);
+    }
+}
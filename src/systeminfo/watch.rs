@@ -0,0 +1,132 @@
+use std::{
+    ffi::CString,
+    fs::File,
+    io::{self, Read},
+    os::{fd::FromRawFd, unix::ffi::OsStrExt},
+    path::Path,
+    sync::mpsc,
+    thread,
+};
+
+use super::System;
+
+/// The directories os-release is in. `/etc/os-release` is usually a link to the one in `/usr/lib`,
+/// and upgrades replace the files rather than write to them, so the directories are watched
+const OS_RELEASE_DIRS: [&str; 2] = ["/etc", "/usr/lib"];
+
+const OS_RELEASE: &[u8] = b"os-release";
+
+/// The size of `struct inotify_event`, without the name that follows it
+const EVENT_HEADER_LEN: usize = 16;
+
+impl System {
+    /// Sends on `tx` whenever os-release is written, replaced or removed, so the system can be
+    /// `refresh()`ed. The watch runs on a thread of its own, which stops at the first change after
+    /// the receiver is dropped
+    pub fn watch(tx: mpsc::Sender<()>) -> io::Result<()> {
+        watch_dirs(&OS_RELEASE_DIRS.map(Path::new), tx)
+    }
+}
+
+fn watch_dirs(dirs: &[&Path], tx: mpsc::Sender<()>) -> io::Result<()> {
+    // SAFETY: inotify_init1 has no preconditions
+    let fd = unsafe { libc::inotify_init1(libc::IN_CLOEXEC) };
+    if fd < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    // SAFETY: the descriptor was just opened, and nothing else owns it. The file closes it
+    let mut inotify = unsafe { File::from_raw_fd(fd) };
+
+    let mask = libc::IN_CLOSE_WRITE | libc::IN_MOVED_TO | libc::IN_CREATE | libc::IN_DELETE;
+    let mut error = None;
+    let mut watching = false;
+    for dir in dirs {
+        let path = CString::new(dir.as_os_str().as_bytes())?;
+        // SAFETY: the descriptor is open, and `path` is nul terminated and outlives the call
+        if unsafe { libc::inotify_add_watch(fd, path.as_ptr(), mask) } < 0 {
+            error = Some(io::Error::last_os_error());
+        } else {
+            watching = true;
+        }
+    }
+    if !watching {
+        return Err(error.unwrap_or_else(|| io::Error::other("there's nothing to watch")));
+    }
+
+    thread::spawn(move || {
+        // Big enough for plenty of events, each is the header and a name of at most NAME_MAX
+        let mut buf = [0u8; 4096];
+        loop {
+            let len = match inotify.read(&mut buf) {
+                Ok(len) => len,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                Err(_) => return,
+            };
+            if event_names(&buf[..len]).any(|name| name == OS_RELEASE) && tx.send(()).is_err() {
+                return;
+            }
+        }
+    });
+    Ok(())
+}
+
+/// The names of the files the events read from inotify are about. The kernel pads them with nul
+/// bytes, those are left out
+fn event_names(mut events: &[u8]) -> impl Iterator<Item = &[u8]> {
+    std::iter::from_fn(move || {
+        let header = events.get(..EVENT_HEADER_LEN)?;
+        let name_len = u32::from_ne_bytes(header[12..16].try_into().unwrap()) as usize;
+        let name = events.get(EVENT_HEADER_LEN..EVENT_HEADER_LEN + name_len)?;
+        events = &events[EVENT_HEADER_LEN + name_len..];
+        let end = name
+            .iter()
+            .position(|&byte| byte == 0)
+            .unwrap_or(name.len());
+        Some(&name[..end])
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, time::Duration};
+
+    use super::*;
+    use crate::test_dir::TestDir;
+
+    fn event(name: &[u8], padded_len: u32) -> Vec<u8> {
+        let mut event = Vec::new();
+        event.extend_from_slice(&1i32.to_ne_bytes());
+        event.extend_from_slice(&libc::IN_CLOSE_WRITE.to_ne_bytes());
+        event.extend_from_slice(&0u32.to_ne_bytes());
+        event.extend_from_slice(&padded_len.to_ne_bytes());
+        event.extend_from_slice(name);
+        event.resize(EVENT_HEADER_LEN + padded_len as usize, 0);
+        event
+    }
+
+    #[test]
+    fn event_names_are_parsed() {
+        let mut events = event(b"hostname", 16);
+        events.extend(event(b"os-release", 16));
+        let names: Vec<&[u8]> = event_names(&events).collect();
+        assert_eq!(names, [&b"hostname"[..], b"os-release"]);
+        // A cut off event is left out
+        assert_eq!(event_names(&events[..20]).count(), 0);
+    }
+
+    #[test]
+    fn os_release_changes_are_sent() {
+        let dir = TestDir::new("watch");
+        let (tx, rx) = mpsc::channel();
+        watch_dirs(&[&dir], tx).unwrap();
+
+        fs::write(dir.join("hostname"), "archlinux\n").unwrap();
+        assert!(rx.recv_timeout(Duration::from_millis(200)).is_err());
+        fs::write(dir.join("os-release"), "ID=arch\n").unwrap();
+        assert!(rx.recv_timeout(Duration::from_secs(5)).is_ok());
+
+        let path = dir.to_path_buf();
+        drop(dir);
+        assert!(watch_dirs(&[&path], mpsc::channel().0).is_err());
+    }
+}
@@ -0,0 +1,44 @@
+//! A directory for the tests to write their files in. Both the library and the binary declare this
+//! module, so their tests share it
+
+use std::{
+    fs,
+    ops::Deref,
+    path::{Path, PathBuf},
+};
+
+/// An empty directory under the temporary directory, removed again once it's dropped, even if the
+/// test panics
+pub struct TestDir(PathBuf);
+
+impl TestDir {
+    /// `name` keeps the tests that run at the same time apart, the process id keeps apart those of
+    /// test binaries that run at the same time
+    pub fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("linutil-{name}-{}", std::process::id()));
+        // Left over from a run that was killed before it could clean up
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).unwrap();
+        Self(path)
+    }
+}
+
+impl Deref for TestDir {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl AsRef<Path> for TestDir {
+    fn as_ref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TestDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
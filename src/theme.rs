@@ -1,4 +1,8 @@
-use ratatui::style::Color;
+use ratatui::{
+    style::{Color, Style},
+    widgets::{Block, Borders},
+};
+use serde::{Deserialize, Serialize};
 
 pub static mut THEME_IDX: usize = 1;
 
@@ -7,29 +11,127 @@ pub struct Theme {
     pub cmd_color: Color,
     pub dir_icon: &'static str,
     pub cmd_icon: &'static str,
+    /// In front of commands that run with sudo
+    pub sudo_icon: &'static str,
+    /// In front of commands that only run when linutil runs as root
+    pub root_icon: &'static str,
     pub success_color: Color,
     pub fail_color: Color,
+    pub background: Color,
+    pub foreground: Color,
+    /// Behind the highlighted item of a list
+    pub selection: Color,
+    pub border: Color,
+    pub title: Color,
 }
 
-pub const THEMES: [Theme; 2] = [
+impl Theme {
+    pub const fn dark() -> Self {
+        Self {
+            dir_color: Color::Blue,
+            cmd_color: Color::Rgb(204, 224, 208),
+            dir_icon: "  ",
+            cmd_icon: "  ",
+            sudo_icon: "\u{f023} ",
+            root_icon: "\u{f084} ",
+            fail_color: Color::Rgb(199, 55, 44),
+            success_color: Color::Rgb(5, 255, 55),
+            background: Color::Rgb(24, 24, 32),
+            foreground: Color::Rgb(220, 220, 220),
+            selection: Color::Rgb(68, 71, 90),
+            border: Color::Rgb(98, 114, 164),
+            title: Color::Rgb(189, 147, 249),
+        }
+    }
+
+    pub const fn light() -> Self {
+        Self {
+            dir_color: Color::Rgb(30, 80, 200),
+            cmd_color: Color::Rgb(30, 110, 60),
+            dir_icon: "  ",
+            cmd_icon: "  ",
+            sudo_icon: "\u{f023} ",
+            root_icon: "\u{f084} ",
+            fail_color: Color::Rgb(199, 55, 44),
+            success_color: Color::Rgb(0, 140, 40),
+            background: Color::Rgb(250, 250, 250),
+            foreground: Color::Rgb(40, 40, 40),
+            selection: Color::Rgb(200, 210, 230),
+            border: Color::Rgb(120, 120, 140),
+            title: Color::Rgb(60, 60, 160),
+        }
+    }
+
+    /// What everything is drawn with, unless it has a color of its own
+    pub fn base_style(&self) -> Style {
+        Style::default().fg(self.foreground).bg(self.background)
+    }
+
+    /// A block with borders all around, in the colors of the theme
+    pub fn block(&self) -> Block<'static> {
+        Block::default()
+            .borders(Borders::ALL)
+            .style(self.base_style())
+            .border_style(Style::default().fg(self.border))
+            .title_style(Style::default().fg(self.title))
+    }
+}
+
+pub const THEMES: [Theme; 3] = [
     Theme {
         dir_color: Color::Blue,
         cmd_color: Color::LightGreen,
         dir_icon: "[DIR]",
         cmd_icon: "[CMD]",
+        sudo_icon: "[SUDO] ",
+        root_icon: "[ROOT] ",
         success_color: Color::Green,
         fail_color: Color::Red,
+        // Leave the rest to the terminal
+        background: Color::Reset,
+        foreground: Color::Reset,
+        selection: Color::DarkGray,
+        border: Color::Reset,
+        title: Color::Reset,
     },
-    Theme {
-        dir_color: Color::Blue,
-        cmd_color: Color::Rgb(204, 224, 208),
-        dir_icon: "  ",
-        cmd_icon: "  ",
-        fail_color: Color::Rgb(199, 55, 44),
-        success_color: Color::Rgb(5, 255, 55),
-    },
+    Theme::dark(),
+    Theme::light(),
 ];
 
+/// The color themes that can be switched between, and saved
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ThemeChoice {
+    Dark,
+    Light,
+}
+
+impl ThemeChoice {
+    /// The theme in use, `None` in compatibility mode
+    pub fn current() -> Option<Self> {
+        match unsafe { THEME_IDX } {
+            1 => Some(Self::Dark),
+            2 => Some(Self::Light),
+            _ => None,
+        }
+    }
+
+    pub fn toggled(self) -> Self {
+        match self {
+            Self::Dark => Self::Light,
+            Self::Light => Self::Dark,
+        }
+    }
+
+    /// The index of the theme in `THEMES`
+    pub fn index(self) -> usize {
+        match self {
+            Self::Dark => 1,
+            Self::Light => 2,
+        }
+    }
+}
+
 pub fn get_theme() -> &'static Theme {
     &THEMES[unsafe { THEME_IDX }]
 }